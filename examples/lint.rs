@@ -0,0 +1,30 @@
+//! Check FIGfont files for problems: `cargo run --example lint -- fonts/plain/*.flf`
+
+use std::{env, process};
+
+use riglet::{lint_file, Severity};
+
+fn main() {
+    let mut failed = false;
+
+    for path in env::args().skip(1) {
+        match lint_file(&path) {
+            Ok(problems) => {
+                for problem in problems.iter() {
+                    println!("{}:{}", path, problem);
+                }
+                failed |= problems
+                    .iter()
+                    .any(|problem| problem.severity == Severity::Error);
+            }
+            Err(err) => {
+                eprintln!("{}: {}", path, err);
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,135 @@
+use std::{env, path::PathBuf};
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use riglet::{fonts, FIGfont, FIGfontExt, FIGure};
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, Ident, LitInt, LitStr, Token,
+};
+
+const DEFAULT_WIDTH: usize = 80;
+
+struct Figlet {
+    font: Option<LitStr>,
+    width: Option<LitInt>,
+    text: LitStr,
+}
+
+impl Parse for Figlet {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut font = None;
+        let mut width = None;
+        let mut text = None;
+
+        while !input.is_empty() {
+            if input.peek(LitStr) {
+                if text.is_some() {
+                    return Err(input.error("text already specified"));
+                }
+                text = Some(input.parse()?);
+            } else {
+                let key: Ident = input.parse()?;
+                input.parse::<Token![=]>()?;
+
+                match key.to_string().as_str() {
+                    "font" if font.is_none() => font = Some(input.parse()?),
+                    "width" if width.is_none() => width = Some(input.parse()?),
+                    "font" | "width" => {
+                        return Err(syn::Error::new(key.span(), "option already specified"))
+                    }
+                    _ => return Err(syn::Error::new(key.span(), "unknown option")),
+                }
+            }
+
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+
+        match text {
+            Some(text) => Ok(Figlet { font, width, text }),
+            None => Err(syn::Error::new(Span::call_site(), "missing text")),
+        }
+    }
+}
+
+fn load_font(name: &LitStr) -> syn::Result<(FIGfont, String)> {
+    let value = name.value();
+    let mut path = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap_or_default());
+    path.push(&value);
+
+    let font = FIGfont::load_from(&path).map_err(|e| {
+        syn::Error::new(name.span(), format!("cannot load font {:?}: {}", value, e))
+    })?;
+    let path = path
+        .into_os_string()
+        .into_string()
+        .map_err(|_| syn::Error::new(name.span(), "font path is not valid UTF-8"))?;
+
+    Ok((font, path))
+}
+
+/// Renders the banner, returning the path of a loaded `.flf` file too.
+fn render(input: Figlet) -> syn::Result<(String, Option<String>)> {
+    let loaded;
+    let mut path = None;
+    let font = match input.font {
+        Some(ref name) if name.value().ends_with(".flf") => {
+            let (font, font_path) = load_font(name)?;
+            loaded = font;
+            path = Some(font_path);
+            &loaded
+        }
+        Some(ref name) => fonts::by_name(&name.value()).ok_or_else(|| {
+            syn::Error::new(name.span(), format!("unknown font {:?}", name.value()))
+        })?,
+        None => fonts::standard(),
+    };
+    let width = match input.width {
+        Some(ref width) => width.base10_parse()?,
+        None => DEFAULT_WIDTH,
+    };
+
+    let text = input.text.value();
+    if let Some(ch) = text.chars().find(|&ch| !font.has_char(ch)) {
+        return Err(syn::Error::new(
+            input.text.span(),
+            format!("character {:?} is missing from the font", ch),
+        ));
+    }
+
+    let mut figure = FIGure::new(font, width);
+    figure
+        .add(&text)
+        .map_err(|e| syn::Error::new(input.text.span(), e))?;
+
+    Ok((figure.to_string(), path))
+}
+
+/// Render a FIGure at compile time, expanding to a `&'static str`.
+///
+/// ```
+/// const BANNER: &str = riglet_macros::figlet!(font = "slant", width = 40, "Hi");
+/// ```
+///
+/// `font` is either the name of a bundled font (see `riglet::fonts::NAMES`)
+/// or a path to a `.flf` file relative to the crate's manifest directory and
+/// defaults to `"standard"`, `width` defaults to 80.
+#[proc_macro]
+pub fn figlet(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as Figlet);
+
+    match render(input) {
+        // including the font makes cargo rebuild when it changes
+        Ok((banner, Some(path))) => quote!({
+            const _: &[u8] = include_bytes!(#path);
+            #banner
+        })
+        .into(),
+        Ok((banner, None)) => quote!(#banner).into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
@@ -0,0 +1,31 @@
+use riglet::{FIGfont, FIGure};
+use riglet_macros::figlet;
+
+fn render(font: &FIGfont, width: usize, text: &str) -> String {
+    let mut figure = FIGure::new(font, width);
+    figure.add(text).unwrap();
+    figure.to_string()
+}
+
+#[test]
+fn standard() {
+    const BANNER: &str = figlet!("Ciao");
+    let font = FIGfont::standard().unwrap();
+
+    assert_eq!(BANNER, render(&font, 80, "Ciao"));
+}
+
+#[test]
+fn font_and_width() {
+    const BANNER: &str = figlet!(font = "../fonts/plain/slant.flf", width = 30, "Ciao ciao");
+    let font = FIGfont::load_from("../fonts/plain/slant.flf").unwrap();
+
+    assert_eq!(BANNER, render(&font, 30, "Ciao ciao"));
+}
+
+#[test]
+fn bundled() {
+    let banner: &'static str = figlet!(font = "slant", "Ciao");
+
+    assert_eq!(banner, render(riglet::fonts::slant(), 80, "Ciao"));
+}
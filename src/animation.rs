@@ -0,0 +1,269 @@
+use std::{
+    io::{self, Write},
+    thread,
+    time::Duration,
+};
+
+use figfont::subcharacter::SubCharacter;
+
+use crate::{
+    figure::FIGure,
+    grid::{self, Grid},
+};
+
+/// Reveals a text one character at a time, every frame being the FIGure
+/// rendering of a longer prefix, so kerning, smushing and wrapping are always
+/// those of the text shown.
+pub struct Typewriter<'f, 'a> {
+    figure: &'f mut FIGure<'a>,
+    text: String,
+    end: usize,
+}
+
+impl<'f, 'a> Typewriter<'f, 'a> {
+    /// Create the animation of `text`, rendered with the settings of
+    /// `figure`. The text `figure` already contains is cleared.
+    pub fn new<S: Into<String>>(figure: &'f mut FIGure<'a>, text: S) -> Typewriter<'f, 'a> {
+        Typewriter {
+            figure,
+            text: text.into(),
+            end: 0,
+        }
+    }
+}
+
+impl<'f, 'a> Iterator for Typewriter<'f, 'a> {
+    type Item = Grid;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ch = self.text[self.end..].chars().next()?;
+        self.end += ch.len_utf8();
+
+        self.figure.clear();
+        self.figure.add(&self.text[..self.end]).ok()?;
+        Some(self.figure.grid())
+    }
+}
+
+/// Scrolls a grid from right to left across a window `width` cells wide,
+/// from the first column entering on the right to the last one leaving on
+/// the left.
+pub struct Marquee {
+    rows: Vec<Vec<SubCharacter>>,
+    baseline: usize,
+    hardblank: String,
+    width: usize,
+    step: usize,
+}
+
+impl Marquee {
+    /// Create the animation of `grid`, usually the rendering of a FIGure
+    /// wide enough to keep its text on a single line.
+    pub fn new(grid: &Grid, width: usize) -> Marquee {
+        let len = grid.rows().iter().map(Vec::len).max().unwrap_or(0);
+        let rows = grid
+            .rows()
+            .iter()
+            .map(|row| {
+                // the grid enters and leaves through `width` blank cells
+                let mut res = vec![grid::space(); width];
+                res.extend(row.iter().cloned());
+                res.resize(len + 2 * width, grid::space());
+                res
+            })
+            .collect();
+
+        Marquee {
+            rows,
+            baseline: grid.baseline(),
+            hardblank: grid.hardblank().to_string(),
+            width,
+            step: 0,
+        }
+    }
+}
+
+impl Iterator for Marquee {
+    type Item = Grid;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let len = self.rows.first().map_or(0, Vec::len);
+        if self.step + self.width + 1 >= len {
+            return None;
+        }
+        self.step += 1;
+
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| row[self.step..self.step + self.width].to_vec())
+            .collect();
+        Some(Grid::new(rows, self.baseline, self.hardblank.clone()))
+    }
+}
+
+/// Scrolls a grid from bottom to top across a window `height` rows tall,
+/// from the first row entering at the bottom to the last one leaving at the
+/// top.
+pub struct Scroll {
+    rows: Vec<Vec<SubCharacter>>,
+    hardblank: String,
+    height: usize,
+    step: usize,
+}
+
+impl Scroll {
+    pub fn new(grid: &Grid, height: usize) -> Scroll {
+        let mut rows = vec![Vec::new(); height];
+        rows.extend(grid.rows().iter().cloned());
+        rows.extend(std::iter::repeat(Vec::new()).take(height));
+
+        Scroll {
+            rows,
+            hardblank: grid.hardblank().to_string(),
+            height,
+            step: 0,
+        }
+    }
+}
+
+impl Iterator for Scroll {
+    type Item = Grid;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.step + self.height + 1 >= self.rows.len() {
+            return None;
+        }
+        self.step += 1;
+
+        let rows = self.rows[self.step..self.step + self.height].to_vec();
+        Some(Grid::new(rows, self.height, self.hardblank.clone()))
+    }
+}
+
+/// Play `frames` on a terminal, waiting `delay` after each one.
+///
+/// Every frame is drawn over the previous one by moving the cursor back up,
+/// instead of clearing the screen, so the animation doesn't flicker.
+pub fn play<I, W>(frames: I, out: &mut W, delay: Duration) -> io::Result<()>
+where
+    I: IntoIterator<Item = Grid>,
+    W: Write,
+{
+    let mut drawn = 0;
+    let frames = move |out: &mut W| -> io::Result<()> {
+        for frame in frames {
+            if drawn > 0 {
+                write!(out, "\x1b[{}A\r", drawn)?;
+            }
+
+            let rows = frame.to_rows();
+            for row in rows.iter() {
+                writeln!(out, "{}\x1b[K", row)?;
+            }
+            // blank the rows left over by a taller frame
+            for _ in rows.len()..drawn {
+                writeln!(out, "\x1b[K")?;
+            }
+            drawn = std::cmp::max(drawn, rows.len());
+
+            out.flush()?;
+            thread::sleep(delay);
+        }
+        Ok(())
+    };
+
+    // hide the cursor while playing, showing it again even on errors
+    let res = write!(out, "\x1b[?25l").and_then(|_| frames(out));
+    let shown = write!(out, "\x1b[?25h").and_then(|_| out.flush());
+    res.and(shown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{play, Marquee, Scroll, Typewriter};
+    use crate::{figure::FIGure, grid::Grid};
+    use figfont::{subcharacter::SubCharacter, FIGfont};
+    use std::{
+        io::{self, Write},
+        time::Duration,
+    };
+
+    fn grid(rows: &[&str]) -> Grid {
+        let rows = rows
+            .iter()
+            .map(|row| row.chars().map(SubCharacter::from).collect())
+            .collect();
+        Grid::new(rows, 1, " ")
+    }
+
+    #[test]
+    fn typewriter() {
+        let font = FIGfont::standard().unwrap();
+        let mut figure = FIGure::new(&font, 80);
+        let frames: Vec<_> = Typewriter::new(&mut figure, "Hey").collect();
+
+        let mut expected = FIGure::new(&font, 80);
+        expected.add("He").unwrap();
+
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[1], expected.grid());
+    }
+
+    #[test]
+    fn marquee() {
+        let frames: Vec<_> = Marquee::new(&grid(&["ab"]), 2)
+            .map(|frame| frame.to_rows().remove(0))
+            .collect();
+
+        assert_eq!(frames, vec![" a", "ab", "b "]);
+    }
+
+    #[test]
+    fn scroll() {
+        let frames: Vec<_> = Scroll::new(&grid(&["a", "b"]), 2)
+            .map(|frame| frame.to_rows())
+            .collect();
+
+        assert_eq!(frames, vec![vec!["", "a"], vec!["a", "b"], vec!["b", ""]]);
+    }
+
+    #[test]
+    fn player() {
+        let mut out = Vec::new();
+        play(
+            vec![grid(&["a", "b"]), grid(&["c"])],
+            &mut out,
+            Duration::ZERO,
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\x1b[?25la\x1b[K\nb\x1b[K\n\x1b[2A\rc\x1b[K\n\x1b[K\n\x1b[?25h"
+        );
+
+        // a broken pipe after the first frame still shows the cursor
+        struct Broken(Vec<u8>, bool);
+        impl Write for Broken {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.write(buf)
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                if std::mem::replace(&mut self.1, false) {
+                    Err(io::ErrorKind::BrokenPipe.into())
+                } else {
+                    Ok(())
+                }
+            }
+        }
+        let mut out = Broken(Vec::new(), true);
+        let res = play(vec![grid(&["a"]), grid(&["b"])], &mut out, Duration::ZERO);
+
+        assert_eq!(res.unwrap_err().kind(), io::ErrorKind::BrokenPipe);
+        assert_eq!(
+            String::from_utf8(out.0).unwrap(),
+            "\x1b[?25la\x1b[K\n\x1b[?25h"
+        );
+    }
+}
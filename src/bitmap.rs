@@ -0,0 +1,571 @@
+use std::{collections::BTreeMap, convert::TryFrom, fs, io, path::Path};
+
+use figfont::{header::Layout, subcharacter::SubCharacter, FIGfont};
+use thiserror::Error;
+
+use crate::writer::{FontWriter, WriteError};
+
+const PSF1_MAGIC: &[u8] = &[0x36, 0x04];
+const PSF2_MAGIC: &[u8] = &[0x72, 0xb5, 0x4a, 0x86];
+/// The largest BDF bounding box accepted, in pixels wide or high.
+const BDF_MAX_SIZE: i64 = 4096;
+
+#[derive(Debug, Error)]
+pub enum BitmapError {
+    #[error("invalid BDF font: {0}")]
+    Bdf(String),
+    #[error("invalid PSF font: {0}")]
+    Psf(String),
+    #[error("unknown bitmap font format")]
+    Format,
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// How pixels are drawn with sub-characters.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Pixels {
+    /// A sub-character for every set pixel.
+    Char(char),
+    /// Two rows of pixels in every row of sub-characters, drawn with `▀`,
+    /// `▄` and `█`.
+    HalfBlocks,
+}
+
+impl Default for Pixels {
+    fn default() -> Self {
+        Pixels::Char('#')
+    }
+}
+
+/// A bitmap font, every glyph being a cell of rows of pixels with the
+/// baseline `ascent` rows from the top.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitmapFont {
+    height: usize,
+    ascent: usize,
+    glyphs: BTreeMap<i32, Vec<Vec<bool>>>,
+}
+
+fn bdf_error<S: Into<String>>(msg: S) -> BitmapError {
+    BitmapError::Bdf(msg.into())
+}
+
+fn psf_error<S: Into<String>>(msg: S) -> BitmapError {
+    BitmapError::Psf(msg.into())
+}
+
+fn numbers(args: &[&str], n: usize, what: &str) -> Result<Vec<i64>, BitmapError> {
+    let res: Vec<i64> = args
+        .iter()
+        .take(n)
+        .map(|arg| arg.parse())
+        .collect::<Result<_, _>>()
+        .map_err(|_| bdf_error(format!("invalid {}", what)))?;
+
+    if res.len() == n {
+        Ok(res)
+    } else {
+        Err(bdf_error(format!("invalid {}", what)))
+    }
+}
+
+fn hex_digit(digit: u8) -> u8 {
+    match digit {
+        b'0'..=b'9' => digit - b'0',
+        b'a'..=b'f' => digit - b'a' + 10,
+        _ => digit - b'A' + 10,
+    }
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Result<usize, BitmapError> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
+        .ok_or_else(|| psf_error("truncated header"))
+}
+
+impl BitmapFont {
+    /// Load the BDF or PSF font at `path`, telling them apart by content.
+    pub fn load_from<P: AsRef<Path>>(path: P) -> Result<BitmapFont, BitmapError> {
+        let data = fs::read(path)?;
+
+        if data.starts_with(PSF1_MAGIC) || data.starts_with(PSF2_MAGIC) {
+            BitmapFont::parse_psf(&data)
+        } else if data.starts_with(b"STARTFONT") {
+            BitmapFont::parse_bdf(&data)
+        } else {
+            Err(BitmapError::Format)
+        }
+    }
+
+    /// Parse a BDF font. Glyphs are placed in cells as tall as the font's
+    /// ascent and descent, and as wide as their advance.
+    pub fn parse_bdf(data: &[u8]) -> Result<BitmapFont, BitmapError> {
+        let text = String::from_utf8_lossy(data);
+        let mut lines = text.lines();
+
+        let mut bbox: Option<Vec<i64>> = None;
+        let (mut ascent, mut descent) = (None, None);
+        let mut glyphs = Vec::new();
+
+        while let Some(line) = lines.next() {
+            let mut args = line.split_whitespace();
+            let keyword = args.next().unwrap_or_default();
+            let rest: Vec<&str> = args.collect();
+
+            match keyword {
+                "FONTBOUNDINGBOX" => {
+                    let numbers = numbers(&rest, 4, "FONTBOUNDINGBOX")?;
+                    let size = 0..=BDF_MAX_SIZE;
+                    if !numbers
+                        .iter()
+                        .all(|n| (-BDF_MAX_SIZE..=BDF_MAX_SIZE).contains(n))
+                        || !size.contains(&numbers[0])
+                        || !size.contains(&numbers[1])
+                    {
+                        return Err(bdf_error("invalid FONTBOUNDINGBOX"));
+                    }
+                    bbox = Some(numbers);
+                    continue;
+                }
+                "FONT_ASCENT" => {
+                    let n = numbers(&rest, 1, "FONT_ASCENT")?[0];
+                    ascent = Some(n.clamp(0, BDF_MAX_SIZE));
+                    continue;
+                }
+                "FONT_DESCENT" => {
+                    let n = numbers(&rest, 1, "FONT_DESCENT")?[0];
+                    descent = Some(n.clamp(0, BDF_MAX_SIZE));
+                    continue;
+                }
+                "STARTCHAR" => (),
+                _ => continue,
+            }
+
+            let bbox = bbox
+                .clone()
+                .ok_or_else(|| bdf_error("missing FONTBOUNDINGBOX"))?;
+            let mut encoding = -1;
+            let mut advance = bbox[0];
+            let mut glyph_box = bbox.clone();
+            let mut rows = Vec::new();
+
+            while let Some(line) = lines.next() {
+                let mut args = line.split_whitespace();
+                let keyword = args.next().unwrap_or_default();
+                let rest: Vec<&str> = args.collect();
+
+                match keyword {
+                    "ENCODING" => encoding = numbers(&rest, 1, "ENCODING")?[0],
+                    "DWIDTH" => advance = numbers(&rest, 1, "DWIDTH")?[0],
+                    "BBX" => {
+                        glyph_box = numbers(&rest, 4, "BBX")?;
+                        // the glyph is within the font's bounding box
+                        let (w, h, x, y) = (glyph_box[0], glyph_box[1], glyph_box[2], glyph_box[3]);
+                        if w < 0
+                            || h < 0
+                            || x < bbox[2]
+                            || y < bbox[3]
+                            || x + w > bbox[2] + bbox[0]
+                            || y + h > bbox[3] + bbox[1]
+                        {
+                            return Err(bdf_error("BBX outside of FONTBOUNDINGBOX"));
+                        }
+                    }
+                    "BITMAP" => {
+                        for _ in 0..glyph_box[1] {
+                            let row = lines.next().ok_or_else(|| bdf_error("truncated BITMAP"))?;
+                            let hex = row.trim().as_bytes();
+                            if !hex.iter().all(u8::is_ascii_hexdigit) {
+                                return Err(bdf_error("invalid BITMAP row"));
+                            }
+                            let bytes: Vec<u8> = hex
+                                .chunks_exact(2)
+                                .map(|pair| (hex_digit(pair[0]) << 4) | hex_digit(pair[1]))
+                                .collect();
+                            rows.push(bytes);
+                        }
+                    }
+                    "ENDCHAR" => break,
+                    _ => (),
+                }
+            }
+
+            let advance = advance.clamp(0, BDF_MAX_SIZE);
+            // encodings past i32 can't be code tags
+            if let Ok(code) = i32::try_from(encoding) {
+                if code >= 0 {
+                    glyphs.push((code, advance, glyph_box, rows));
+                }
+            }
+        }
+
+        let bbox = bbox.ok_or_else(|| bdf_error("missing FONTBOUNDINGBOX"))?;
+        let ascent = ascent.unwrap_or(bbox[1] + bbox[3]).max(0);
+        let descent = descent.unwrap_or(-bbox[3]).max(0);
+        let height = (ascent + descent) as usize;
+
+        let mut res = BitmapFont {
+            height,
+            ascent: ascent as usize,
+            glyphs: BTreeMap::new(),
+        };
+        for (code, advance, glyph_box, rows) in glyphs {
+            let (w, h, x, y) = (glyph_box[0], glyph_box[1], glyph_box[2], glyph_box[3]);
+            let shift = (-x).max(0);
+            let width = advance.max(x + w).max(0) + shift;
+            let mut cell = vec![vec![false; width as usize]; height];
+
+            for (r, bytes) in rows.iter().enumerate() {
+                let top = ascent - (y + h) + r as i64;
+                if !(0..height as i64).contains(&top) {
+                    continue;
+                }
+                for c in 0..w {
+                    let byte = bytes.get(c as usize / 8).copied().unwrap_or(0);
+                    if byte & (0x80 >> (c % 8)) != 0 {
+                        let left = x + shift + c;
+                        if (0..width).contains(&left) {
+                            cell[top as usize][left as usize] = true;
+                        }
+                    }
+                }
+            }
+
+            res.glyphs.insert(code, cell);
+        }
+
+        Ok(res)
+    }
+
+    /// Parse a PSF (version 1 or 2) console font. Glyphs are mapped to the
+    /// characters of the font's Unicode table, or to their index without
+    /// one. PSF has no metrics, the baseline is found below the capitals.
+    pub fn parse_psf(data: &[u8]) -> Result<BitmapFont, BitmapError> {
+        let (count, height, width, offset, table) = if data.starts_with(PSF1_MAGIC) {
+            let mode = *data.get(2).ok_or_else(|| psf_error("truncated header"))?;
+            let height = *data.get(3).ok_or_else(|| psf_error("truncated header"))? as usize;
+            let count = if mode & 0x01 != 0 { 512 } else { 256 };
+            (count, height, 8, 4, mode & 0x06 != 0)
+        } else if data.starts_with(PSF2_MAGIC) {
+            let offset = u32_at(data, 8)?;
+            let flags = u32_at(data, 12)?;
+            let count = u32_at(data, 16)?;
+            let height = u32_at(data, 24)?;
+            let width = u32_at(data, 28)?;
+            (count, height, width, offset, flags & 0x01 != 0)
+        } else {
+            return Err(BitmapError::Format);
+        };
+
+        if width == 0 || height == 0 {
+            return Err(psf_error("empty glyphs"));
+        }
+        let row_len = width.div_ceil(8);
+        let end = row_len
+            .checked_mul(height)
+            .and_then(|size| size.checked_mul(count))
+            .and_then(|len| len.checked_add(offset))
+            .ok_or_else(|| psf_error("truncated glyphs"))?;
+        let size = row_len * height;
+        let bitmaps = data
+            .get(offset..end)
+            .ok_or_else(|| psf_error("truncated glyphs"))?;
+
+        let cells: Vec<Vec<Vec<bool>>> = bitmaps
+            .chunks(size)
+            .map(|glyph| {
+                glyph
+                    .chunks(row_len)
+                    .map(|row| {
+                        (0..width)
+                            .map(|c| row[c / 8] & (0x80 >> (c % 8)) != 0)
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut glyphs = BTreeMap::new();
+        if table {
+            let codes = if data.starts_with(PSF1_MAGIC) {
+                psf1_table(&data[end..], count)
+            } else {
+                psf2_table(&data[end..], count)
+            };
+            for (i, codes) in codes.into_iter().enumerate() {
+                for code in codes {
+                    glyphs.entry(code).or_insert_with(|| cells[i].clone());
+                }
+            }
+        } else {
+            for (i, cell) in cells.into_iter().enumerate() {
+                glyphs.insert(i as i32, cell);
+            }
+        }
+
+        // the baseline is under the lowest ink of the capitals
+        let ascent = ['H', 'E', 'X']
+            .iter()
+            .filter_map(|ch| glyphs.get(&(*ch as i32)))
+            .filter_map(|cell: &Vec<Vec<bool>>| cell.iter().rposition(|row| row.contains(&true)))
+            .map(|row| row + 1)
+            .max()
+            .unwrap_or(height);
+
+        Ok(BitmapFont {
+            height,
+            ascent,
+            glyphs,
+        })
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Get the rows above the baseline.
+    pub fn ascent(&self) -> usize {
+        self.ascent
+    }
+
+    /// Get the pixels of the glyph for `code`.
+    pub fn glyph(&self, code: i32) -> Option<&Vec<Vec<bool>>> {
+        self.glyphs.get(&code)
+    }
+
+    fn lines(&self, cell: &[Vec<bool>], pixels: Pixels) -> Vec<Vec<SubCharacter>> {
+        let width = cell.first().map_or(0, Vec::len);
+        let pixel = |y: usize, x: usize| cell.get(y).is_some_and(|row| row[x]);
+        let rows = match pixels {
+            Pixels::Char(_) => self.height,
+            Pixels::HalfBlocks => self.height.div_ceil(2),
+        };
+
+        let mut lines: Vec<Vec<SubCharacter>> = (0..rows)
+            .map(|y| {
+                (0..width)
+                    .map(|x| {
+                        let ch = match pixels {
+                            Pixels::Char(ch) if pixel(y, x) => ch,
+                            Pixels::Char(_) => ' ',
+                            Pixels::HalfBlocks => match (pixel(2 * y, x), pixel(2 * y + 1, x)) {
+                                (true, true) => '█',
+                                (true, false) => '▀',
+                                (false, true) => '▄',
+                                (false, false) => ' ',
+                            },
+                        };
+                        SubCharacter::from(ch)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        keep_advance(&mut lines);
+        lines
+    }
+
+    /// Get a writer for the FIGfont drawing this font with `pixels`.
+    pub fn to_writer(&self, pixels: Pixels) -> FontWriter {
+        let mut writer = FontWriter::empty(match pixels {
+            Pixels::Char(_) => self.height,
+            Pixels::HalfBlocks => self.height.div_ceil(2),
+        });
+        writer.set_baseline(std::cmp::max(
+            match pixels {
+                Pixels::Char(_) => self.ascent,
+                Pixels::HalfBlocks => self.ascent.div_ceil(2),
+            },
+            1,
+        ));
+        writer.set_layout(Layout::HORIZONTAL_KERNING);
+        if pixels == Pixels::Char('$') {
+            writer.set_hardblank("\u{7f}");
+        }
+
+        for (code, cell) in self.glyphs.iter() {
+            writer.set_glyph(*code, self.lines(cell, pixels), None);
+        }
+
+        writer
+    }
+
+    /// Convert to a FIGfont drawing this font with `pixels`.
+    pub fn to_font(&self, pixels: Pixels) -> Result<FIGfont, WriteError> {
+        self.to_writer(pixels).to_font()
+    }
+}
+
+/// Make the blank cells before and after the ink of every row of glyph
+/// `lines` hard blanks, so kerning keeps the glyph's advance and bearings.
+fn keep_advance(lines: &mut [Vec<SubCharacter>]) {
+    let blank = |sch: &SubCharacter| match sch {
+        SubCharacter::Symbol(sym) => sym.trim().is_empty(),
+        SubCharacter::Blank => true,
+    };
+
+    for line in lines.iter_mut() {
+        let first = line
+            .iter()
+            .position(|sch| !blank(sch))
+            .unwrap_or(line.len());
+        let last = line
+            .iter()
+            .rposition(|sch| !blank(sch))
+            .map_or(0, |x| x + 1);
+        for (x, sch) in line.iter_mut().enumerate() {
+            if x < first || x >= last {
+                *sch = SubCharacter::Blank;
+            }
+        }
+    }
+}
+
+/// Read the Unicode table of a PSF1 font: UCS-2 code points, sequences
+/// after 0xfffe, every glyph ending with 0xffff.
+fn psf1_table(data: &[u8], count: usize) -> Vec<Vec<i32>> {
+    let mut res = vec![Vec::new(); count];
+    let mut glyph = 0;
+    let mut sequence = false;
+
+    for unit in data
+        .chunks_exact(2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+    {
+        if glyph >= count {
+            break;
+        }
+        match unit {
+            0xffff => {
+                glyph += 1;
+                sequence = false;
+            }
+            0xfffe => sequence = true,
+            unit if !sequence => res[glyph].push(unit as i32),
+            _ => (),
+        }
+    }
+
+    res
+}
+
+/// Read the Unicode table of a PSF2 font: UTF-8 characters, sequences after
+/// 0xfe, every glyph ending with 0xff.
+fn psf2_table(data: &[u8], count: usize) -> Vec<Vec<i32>> {
+    let mut res = vec![Vec::new(); count];
+
+    for (glyph, entry) in data.split(|&b| b == 0xff).take(count).enumerate() {
+        let single = entry.split(|&b| b == 0xfe).next().unwrap_or_default();
+        res[glyph].extend(String::from_utf8_lossy(single).chars().map(|ch| ch as i32));
+    }
+
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BitmapFont, Pixels};
+    use crate::figure::FIGure;
+
+    const BDF: &str = "STARTFONT 2.1
+FONTBOUNDINGBOX 4 4 0 -1
+STARTPROPERTIES 2
+FONT_ASCENT 3
+FONT_DESCENT 1
+ENDPROPERTIES
+CHARS 2
+STARTCHAR H
+ENCODING 72
+DWIDTH 4 0
+BBX 3 3 0 0
+BITMAP
+A0
+E0
+A0
+ENDCHAR
+STARTCHAR i
+ENCODING 105
+DWIDTH 2 0
+BBX 1 3 0 0
+BITMAP
+80
+00
+80
+ENDCHAR
+ENDFONT
+";
+
+    #[test]
+    fn bdf() {
+        let bitmap = BitmapFont::parse_bdf(BDF.as_bytes()).unwrap();
+        assert_eq!(bitmap.height(), 4);
+        assert_eq!(bitmap.ascent(), 3);
+
+        let font = bitmap.to_font(Pixels::default()).unwrap();
+        assert_eq!(font.header().baseline(), 3);
+        let mut figure = FIGure::new(&font, 80);
+        figure.add("Hi").unwrap();
+        assert_eq!(figure.rows(), vec!["# # # ", "###   ", "# # # ", "      "]);
+
+        // glyphs keep their advance and bearing
+        let bdf =
+            BDF.replacen("DWIDTH 2 0", "DWIDTH 4 0", 1)
+                .replacen("BBX 1 3 0 0", "BBX 1 3 1 0", 1);
+        let font = BitmapFont::parse_bdf(bdf.as_bytes())
+            .unwrap()
+            .to_font(Pixels::default())
+            .unwrap();
+        let mut figure = FIGure::new(&font, 80);
+        figure.add("iHi").unwrap();
+        assert_eq!(
+            figure.rows(),
+            vec![
+                " #  # #  #  ",
+                "    ###     ",
+                " #  # #  #  ",
+                "            "
+            ]
+        );
+
+        let font = bitmap.to_font(Pixels::HalfBlocks).unwrap();
+        let mut figure = FIGure::new(&font, 80);
+        figure.add("H").unwrap();
+        assert_eq!(figure.rows(), vec!["█▄█ ", "▀ ▀ "]);
+
+        for (from, to) in [("E0", "0é"), ("BBX 1 3 0 0", "BBX 1 3000000 0 0")] {
+            let bdf = BDF.replacen(from, to, 1);
+            assert!(BitmapFont::parse_bdf(bdf.as_bytes()).is_err());
+        }
+
+        // encodings past i32 are skipped
+        let bdf = BDF.replacen("ENCODING 72", "ENCODING 4294967368", 1);
+        let bitmap = BitmapFont::parse_bdf(bdf.as_bytes()).unwrap();
+        assert_eq!(bitmap.glyph(72), None);
+        assert!(bitmap.glyph(105).is_some());
+    }
+
+    #[test]
+    fn psf2() {
+        let mut data = vec![0x72, 0xb5, 0x4a, 0x86];
+        for n in [0, 32, 1, 2, 2, 2, 2] {
+            data.extend_from_slice(&(n as u32).to_le_bytes());
+        }
+        data.extend_from_slice(&[0x80, 0x40, 0xc0, 0xc0]);
+        data.extend_from_slice(b"A\xffB\xfeBB\xff");
+
+        let bitmap = BitmapFont::parse_psf(&data).unwrap();
+        assert_eq!(
+            bitmap.glyph('A' as i32),
+            Some(&vec![vec![true, false], vec![false, true]])
+        );
+        assert_eq!(bitmap.glyph('B' as i32), Some(&vec![vec![true, true]; 2]));
+        assert_eq!(bitmap.ascent(), 2);
+
+        // empty glyphs and sizes overflowing
+        assert!(BitmapFont::parse_psf(&[0x36, 0x04, 0, 0]).is_err());
+        data[16..20].copy_from_slice(&u32::MAX.to_le_bytes());
+        data[24..32].copy_from_slice(&[0xff; 8]);
+        assert!(BitmapFont::parse_psf(&data).is_err());
+    }
+}
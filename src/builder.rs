@@ -0,0 +1,305 @@
+use figfont::{header::Layout, subcharacter::SubCharacter, FIGfont, PrintDirection};
+use thiserror::Error;
+
+use crate::writer::{FontWriter, WriteError};
+
+#[derive(Debug, Error)]
+pub enum BuildError {
+    #[error("the font must be at least a row tall")]
+    Height,
+    #[error("baseline {baseline} is outside of the font's {height} rows")]
+    Baseline { baseline: usize, height: usize },
+    #[error("the hard blank {0:?} can't be used")]
+    HardBlank(char),
+    #[error("character {code} has {found} rows, the font is {expected} rows tall")]
+    GlyphHeight {
+        code: i32,
+        expected: usize,
+        found: usize,
+    },
+    #[error("character {0} has a line break in a row")]
+    LineBreak(i32),
+    #[error("character {0} has rows without its endmark")]
+    Endmark(i32),
+    #[error("character {0} uses the hard blank as its endmark")]
+    EndmarkHardBlank(i32),
+    #[error(transparent)]
+    Write(#[from] WriteError),
+}
+
+/// Builds a FIGfont from code.
+///
+/// Glyphs are given as rows of text, where the hard blank character stands
+/// for hard blanks. figfont only makes fonts by parsing them, so the font is
+/// encoded in memory and loaded back, rendering exactly like one loaded from
+/// a file.
+#[derive(Debug, Clone)]
+pub struct FontBuilder {
+    writer: FontWriter,
+    hardblank: char,
+    glyphs: Vec<Glyph>,
+}
+
+#[derive(Debug, Clone)]
+struct Glyph {
+    code: i32,
+    rows: Vec<String>,
+    comment: Option<String>,
+    /// Whether the rows end with endmarks, like in a `.flf` file.
+    endmarked: bool,
+}
+
+impl Glyph {
+    /// Get the endmark, the last character of the last row.
+    fn endmark(&self) -> Option<char> {
+        self.rows.last().and_then(|row| row.chars().last())
+    }
+
+    /// Get the rows without their endmarks: one at the end of every row, and
+    /// a second one at the end of the last row.
+    fn rows(&self) -> Vec<&str> {
+        match self.endmark() {
+            Some(mark) if self.endmarked => self
+                .rows
+                .iter()
+                .enumerate()
+                .map(|(i, row)| {
+                    let row = row.strip_suffix(mark).unwrap_or(row);
+                    if i + 1 == self.rows.len() {
+                        row.strip_suffix(mark).unwrap_or(row)
+                    } else {
+                        row
+                    }
+                })
+                .collect(),
+            _ => self.rows.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+impl FontBuilder {
+    pub fn new(height: usize) -> FontBuilder {
+        FontBuilder {
+            writer: FontWriter::empty(height),
+            hardblank: '$',
+            glyphs: Vec::new(),
+        }
+    }
+
+    /// Set the baseline, counted in rows from the top. It defaults to the
+    /// font's height.
+    pub fn baseline(mut self, baseline: usize) -> FontBuilder {
+        self.writer.set_baseline(baseline);
+        self
+    }
+
+    /// Set the hard blank character, `$` by default.
+    pub fn hardblank(mut self, hardblank: char) -> FontBuilder {
+        self.hardblank = hardblank;
+        self
+    }
+
+    /// Set the layout, kerning by default.
+    pub fn layout(mut self, layout: Layout) -> FontBuilder {
+        self.writer.set_layout(layout);
+        self
+    }
+
+    pub fn print_direction(mut self, print_direction: PrintDirection) -> FontBuilder {
+        self.writer.set_print_direction(print_direction);
+        self
+    }
+
+    pub fn comment<S: Into<String>>(mut self, comment: S) -> FontBuilder {
+        self.writer.set_comment(comment);
+        self
+    }
+
+    /// Add the glyph of `ch`, its rows taken as they are.
+    pub fn glyph(self, ch: char, rows: &[&str]) -> FontBuilder {
+        self.glyph_code(ch as i32, rows, None)
+    }
+
+    /// Add the glyph of `code`, with the comment of its code tag if it
+    /// isn't a required character.
+    pub fn glyph_code(self, code: i32, rows: &[&str], comment: Option<&str>) -> FontBuilder {
+        self.push(code, rows, comment, false)
+    }
+
+    /// Add the glyph of `ch` from rows ending with endmarks, as in a `.flf`
+    /// file: the last character of the last row is the endmark, ending every
+    /// row once and the last one once or twice.
+    pub fn glyph_flf(self, ch: char, rows: &[&str]) -> FontBuilder {
+        self.glyph_code_flf(ch as i32, rows, None)
+    }
+
+    /// Add the glyph of `code` from rows ending with endmarks, with the
+    /// comment of its code tag if it isn't a required character.
+    pub fn glyph_code_flf(self, code: i32, rows: &[&str], comment: Option<&str>) -> FontBuilder {
+        self.push(code, rows, comment, true)
+    }
+
+    fn push(
+        mut self,
+        code: i32,
+        rows: &[&str],
+        comment: Option<&str>,
+        endmarked: bool,
+    ) -> FontBuilder {
+        self.glyphs.retain(|glyph| glyph.code != code);
+        self.glyphs.push(Glyph {
+            code,
+            rows: rows.iter().map(|row| row.to_string()).collect(),
+            comment: comment.map(str::to_string),
+            endmarked,
+        });
+        self
+    }
+
+    fn validate(&self) -> Result<(), BuildError> {
+        let height = self.writer.height();
+        if height == 0 {
+            return Err(BuildError::Height);
+        }
+
+        let baseline = self.writer.baseline();
+        if baseline == 0 || baseline > height {
+            return Err(BuildError::Baseline { baseline, height });
+        }
+
+        // the hard blank is a single ISO-8859-1 byte that isn't a blank
+        if self.hardblank as u32 > 0xff || matches!(self.hardblank, ' ' | '\r' | '\n') {
+            return Err(BuildError::HardBlank(self.hardblank));
+        }
+
+        for glyph in self.glyphs.iter() {
+            if glyph.rows.len() != height {
+                return Err(BuildError::GlyphHeight {
+                    code: glyph.code,
+                    expected: height,
+                    found: glyph.rows.len(),
+                });
+            }
+            if glyph.rows.iter().any(|row| row.contains(['\n', '\r'])) {
+                return Err(BuildError::LineBreak(glyph.code));
+            }
+            if glyph.endmarked {
+                match glyph.endmark() {
+                    Some(mark) if mark == self.hardblank => {
+                        return Err(BuildError::EndmarkHardBlank(glyph.code))
+                    }
+                    Some(mark) if glyph.rows.iter().all(|row| row.ends_with(mark)) => (),
+                    _ => return Err(BuildError::Endmark(glyph.code)),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get a writer for the font, to save it.
+    pub fn to_writer(&self) -> Result<FontWriter, BuildError> {
+        self.validate()?;
+
+        let mut writer = self.writer.clone();
+        writer.set_hardblank(self.hardblank.to_string());
+        for glyph in self.glyphs.iter() {
+            let lines = glyph
+                .rows()
+                .iter()
+                .map(|row| {
+                    row.chars()
+                        .map(|ch| {
+                            if ch == self.hardblank {
+                                SubCharacter::Blank
+                            } else {
+                                SubCharacter::from(ch)
+                            }
+                        })
+                        .collect()
+                })
+                .collect();
+            writer.set_glyph(glyph.code, lines, glyph.comment.clone());
+        }
+
+        Ok(writer)
+    }
+
+    /// Build the font. Required characters without a glyph are empty.
+    pub fn build(&self) -> Result<FIGfont, BuildError> {
+        Ok(self.to_writer()?.to_font()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BuildError, FontBuilder};
+    use crate::figure::FIGure;
+    use figfont::header::Layout;
+
+    #[test]
+    fn build() {
+        let font = FontBuilder::new(3)
+            .baseline(2)
+            .layout(Layout::HORIZONTAL_KERNING)
+            .glyph_flf(' ', &["$@", "$@", "$@@"])
+            .glyph('I', &["###", " # ", "###"])
+            .glyph('-', &["    ", "--- ", "    "])
+            .build()
+            .unwrap();
+
+        assert_eq!(font.header().height(), 3);
+        assert_eq!(font.header().baseline(), 2);
+
+        let mut figure = FIGure::new(&font, 80);
+        figure.add("I-I").unwrap();
+        assert_eq!(figure.rows(), vec!["### ###", " #---# ", "### ###"]);
+
+        // rows are taken as they are, whatever they end with
+        let font = FontBuilder::new(3)
+            .glyph('X', &["##", "##", "##"])
+            .glyph_flf('@', &["@#", "@#", "@##"])
+            .build()
+            .unwrap();
+        let mut figure = FIGure::new(&font, 80);
+        figure.add("X@").unwrap();
+        assert_eq!(figure.rows(), vec!["##@", "##@", "##@"]);
+
+        // only the last row ends with two endmarks, others keep their ink
+        let font = FontBuilder::new(2)
+            .glyph_flf('a', &["a@@", "a@@"])
+            .build()
+            .unwrap();
+        let mut figure = FIGure::new(&font, 80);
+        figure.add("a").unwrap();
+        assert_eq!(figure.rows(), vec!["a@", "a "]);
+    }
+
+    #[test]
+    fn validate() {
+        let res = FontBuilder::new(2).glyph('a', &["a"]).build();
+        assert!(matches!(
+            res,
+            Err(BuildError::GlyphHeight {
+                code: 97,
+                expected: 2,
+                found: 1
+            })
+        ));
+
+        let res = FontBuilder::new(2).baseline(3).build();
+        assert!(matches!(res, Err(BuildError::Baseline { .. })));
+
+        let res = FontBuilder::new(1).glyph('a', &["a\nb"]).build();
+        assert!(matches!(res, Err(BuildError::LineBreak(97))));
+
+        let res = FontBuilder::new(2).glyph_flf('a', &["a", "a@@"]).build();
+        assert!(matches!(res, Err(BuildError::Endmark(97))));
+
+        let res = FontBuilder::new(1).glyph_flf('a', &["a$$"]).build();
+        assert!(matches!(res, Err(BuildError::EndmarkHardBlank(97))));
+
+        let res = FontBuilder::new(1).hardblank('\n').build();
+        assert!(matches!(res, Err(BuildError::HardBlank('\n'))));
+    }
+}
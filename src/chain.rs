@@ -0,0 +1,117 @@
+use std::{borrow::Cow, sync::Arc};
+
+use figfont::{subcharacter::SubCharacter, FIGfont};
+
+use crate::font::FIGfontExt;
+
+/// A primary FIGfont followed by fallback fonts, tried in order for every
+/// character the primary font lacks. Clones share the fallbacks.
+#[derive(Clone)]
+pub struct FontChain<'a> {
+    primary: &'a FIGfont,
+    fallbacks: Arc<Vec<&'a FIGfont>>,
+}
+
+impl<'a> FontChain<'a> {
+    pub fn new<'b>(primary: &'b FIGfont) -> FontChain<'b> {
+        FontChain {
+            primary,
+            fallbacks: Arc::new(Vec::new()),
+        }
+    }
+
+    pub fn push(&mut self, fallback: &'a FIGfont) {
+        Arc::make_mut(&mut self.fallbacks).push(fallback);
+    }
+
+    pub fn primary(&self) -> &'a FIGfont {
+        self.primary
+    }
+
+    pub fn fallbacks(&self) -> &[&'a FIGfont] {
+        &self.fallbacks[..]
+    }
+
+    /// Check if any font in the chain defines its own glyph for `code`.
+    pub fn has_glyph(&self, code: i32) -> bool {
+        self.primary.has_glyph(code) || self.fallbacks.iter().any(|font| font.has_glyph(code))
+    }
+
+    /// Get the glyph for `code` from the first font defining it, fitted to
+    /// the primary font's height. If no font defines it the primary font's
+    /// missing character glyph is returned.
+    pub fn glyph(&self, code: i32) -> Cow<'a, Vec<Vec<SubCharacter>>> {
+        if !self.primary.has_glyph(code) {
+            if let Some(font) = self.fallbacks.iter().find(|font| font.has_glyph(code)) {
+                return Cow::Owned(fit(
+                    font.glyph(code).into_owned(),
+                    font.header().baseline(),
+                    self.primary.header().height(),
+                    self.primary.header().baseline(),
+                ));
+            }
+        }
+
+        self.primary.glyph(code)
+    }
+}
+
+/// Fit a glyph to `height` rows: shorter glyphs are padded with spaces so
+/// that their baselines line up, taller ones are scaled down.
+fn fit(
+    lines: Vec<Vec<SubCharacter>>,
+    baseline: usize,
+    height: usize,
+    target_baseline: usize,
+) -> Vec<Vec<SubCharacter>> {
+    let len = lines.first().map_or(0, Vec::len);
+
+    if lines.len() > height {
+        return (0..height)
+            .map(|i| lines[i * lines.len() / height].clone())
+            .collect();
+    }
+
+    let spare = height - lines.len();
+    let top = std::cmp::min(target_baseline.saturating_sub(baseline), spare);
+    let blank = vec![SubCharacter::Symbol(" ".to_string()); len];
+
+    let mut res = Vec::with_capacity(height);
+    res.extend(std::iter::repeat(blank.clone()).take(top));
+    res.extend(lines);
+    res.extend(std::iter::repeat(blank).take(spare - top));
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FontChain;
+    use figfont::FIGfont;
+
+    #[test]
+    fn pad_to_baseline() {
+        let primary = FIGfont::load_from("fonts/plain/banner.flf").unwrap();
+        let fallback = FIGfont::standard().unwrap();
+        let mut chain = FontChain::new(&primary);
+
+        assert!(!chain.has_glyph(0x107));
+        chain.push(&fallback);
+        assert!(chain.has_glyph(0x107));
+
+        let glyph = chain.glyph(0x107);
+        assert_eq!(glyph.len(), primary.header().height());
+        assert!(glyph.iter().all(|line| line.len() == glyph[0].len()));
+        assert!(glyph[0].iter().all(|c| c.to_string() == " "));
+    }
+
+    #[test]
+    fn scale_down() {
+        let primary = FIGfont::load_from("fonts/plain/term.flf").unwrap();
+        let fallback = FIGfont::standard().unwrap();
+        let mut chain = FontChain::new(&primary);
+        chain.push(&fallback);
+
+        assert_eq!(chain.glyph(0x114).len(), 1);
+        assert_eq!(chain.glyph('a' as i32), primary.get('a' as i32).lines());
+    }
+}
@@ -0,0 +1,72 @@
+use std::{collections::HashMap, iter::FromIterator};
+
+/// A character remapping table, applied before glyph lookup.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CharMap {
+    map: HashMap<char, char>,
+}
+
+impl CharMap {
+    pub fn new() -> CharMap {
+        CharMap::default()
+    }
+
+    /// The table used by figlet's `-D` (Deutsch) mode: `[ \ ] { | } ~` are
+    /// mapped to `Ä Ö Ü ä ö ü ß`.
+    pub fn deutsch() -> CharMap {
+        "[\\]{|}~".chars().zip("ÄÖÜäöüß".chars()).collect()
+    }
+
+    pub fn insert(&mut self, from: char, to: char) -> Option<char> {
+        self.map.insert(from, to)
+    }
+
+    pub fn remove(&mut self, from: char) -> Option<char> {
+        self.map.remove(&from)
+    }
+
+    pub fn get(&self, from: char) -> Option<char> {
+        self.map.get(&from).copied()
+    }
+
+    /// Remap `ch`, leaving it untouched if the table has no entry for it.
+    pub fn map(&self, ch: char) -> char {
+        self.get(ch).unwrap_or(ch)
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
+impl FromIterator<(char, char)> for CharMap {
+    fn from_iter<I: IntoIterator<Item = (char, char)>>(iter: I) -> Self {
+        CharMap {
+            map: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Extend<(char, char)> for CharMap {
+    fn extend<I: IntoIterator<Item = (char, char)>>(&mut self, iter: I) {
+        self.map.extend(iter);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CharMap;
+
+    #[test]
+    fn deutsch() {
+        let map = CharMap::deutsch();
+
+        assert_eq!(map.len(), 7);
+        let mapped: String = "[a\\b]{|}~".chars().map(|ch| map.map(ch)).collect();
+        assert_eq!(mapped, "ÄaÖbÜäöüß");
+    }
+}
@@ -0,0 +1,205 @@
+use figfont::{header::Layout, subcharacter::SubCharacter, PrintDirection};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    grid::{self, Grid},
+    line::{apply_kerning, apply_smushing},
+};
+
+/// Vertical alignment of grids placed side by side.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum VAlign {
+    #[default]
+    Top,
+    Middle,
+    Bottom,
+    /// Line up the grids' baselines.
+    Baseline,
+}
+
+/// Horizontal alignment of grids stacked on top of each other.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum HAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// How the touching edges of grids placed side by side are joined.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Spacing {
+    /// Leave this many columns between grids.
+    Gap(usize),
+    /// Move grids together until they touch.
+    Kerning,
+    /// Move grids together until they touch, then smush their edges with
+    /// the horizontal smushing rules in `Layout`.
+    Smushing(Layout),
+}
+
+impl Default for Spacing {
+    fn default() -> Self {
+        Spacing::Gap(1)
+    }
+}
+
+fn blank_row(width: usize) -> Vec<SubCharacter> {
+    vec![grid::space(); width]
+}
+
+/// Place grids side by side, left to right, in a single block.
+pub fn side_by_side(grids: &[Grid], spacing: Spacing, align: VAlign) -> Grid {
+    let hardblank = grids.first().map_or(" ", Grid::hardblank).to_string();
+    let grids: Vec<&Grid> = grids.iter().filter(|grid| !grid.is_empty()).collect();
+
+    let max_baseline = grids.iter().map(|grid| grid.baseline()).max().unwrap_or(0);
+    let height = grids
+        .iter()
+        .map(|grid| match align {
+            VAlign::Baseline => max_baseline - grid.baseline() + grid.height(),
+            _ => grid.height(),
+        })
+        .max()
+        .unwrap_or(0);
+
+    let mut res: Vec<Vec<SubCharacter>> = vec![Vec::new(); height];
+    let mut baseline = 0;
+
+    for (i, grid) in grids.iter().enumerate() {
+        let width = grid.width();
+        let top = match align {
+            VAlign::Top => 0,
+            VAlign::Middle => (height - grid.height()) / 2,
+            VAlign::Bottom => height - grid.height(),
+            VAlign::Baseline => max_baseline - grid.baseline(),
+        };
+
+        let mut block = Grid::clone(grid);
+        block.pad(width);
+        let mut rows = Vec::with_capacity(height);
+        rows.extend(std::iter::repeat(blank_row(width)).take(top));
+        rows.extend(block.into_rows());
+        rows.resize(height, blank_row(width));
+
+        if i == 0 {
+            baseline = top + grid.baseline();
+            res = rows;
+            continue;
+        }
+
+        let layout = match spacing {
+            Spacing::Gap(gap) => {
+                for row in res.iter_mut() {
+                    row.extend(std::iter::repeat(grid::space()).take(gap));
+                }
+                Layout::empty()
+            }
+            Spacing::Kerning => {
+                apply_kerning(&mut res, &mut rows, PrintDirection::LeftToRight);
+                Layout::HORIZONTAL_KERNING
+            }
+            Spacing::Smushing(layout) => {
+                apply_kerning(&mut res, &mut rows, PrintDirection::LeftToRight);
+                layout | Layout::HORIZONTAL_SMUSH
+            }
+        };
+        apply_smushing(&mut res, rows, PrintDirection::LeftToRight, layout);
+    }
+
+    Grid::new(res, baseline, hardblank)
+}
+
+/// Stack grids on top of each other, leaving `gap` blank rows between them.
+pub fn stack(grids: &[Grid], gap: usize, align: HAlign) -> Grid {
+    let hardblank = grids.first().map_or(" ", Grid::hardblank).to_string();
+    let baseline = grids.first().map_or(0, Grid::baseline);
+    let width = grids.iter().map(Grid::width).max().unwrap_or(0);
+    let mut res = Vec::new();
+
+    for (i, grid) in grids.iter().enumerate() {
+        if i != 0 {
+            res.extend(std::iter::repeat(blank_row(width)).take(gap));
+        }
+
+        let indent = match align {
+            HAlign::Left => 0,
+            HAlign::Center => (width - grid.width()) / 2,
+            HAlign::Right => width - grid.width(),
+        };
+        for row in grid.rows() {
+            let mut line = blank_row(indent);
+            line.extend(row.iter().cloned());
+            res.push(line);
+        }
+    }
+
+    let mut res = Grid::new(res, baseline, hardblank);
+    res.pad(width);
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{side_by_side, stack, HAlign, Spacing, VAlign};
+    use crate::{grid::Grid, FIGure};
+    use figfont::{subcharacter::SubCharacter, FIGfont};
+
+    fn grid(rows: &[&str], baseline: usize) -> Grid {
+        let rows = rows
+            .iter()
+            .map(|row| row.chars().map(SubCharacter::from).collect())
+            .collect();
+        Grid::new(rows, baseline, " ")
+    }
+
+    #[test]
+    fn horizontal() {
+        let a = grid(&["ab", "cd"], 2);
+        let b = grid(&["e", "f", "g"], 2);
+
+        let res = side_by_side(&[a.clone(), b.clone()], Spacing::Gap(1), VAlign::Top);
+        assert_eq!(res.to_rows(), vec!["ab e", "cd f", "   g"]);
+
+        let res = side_by_side(&[a.clone(), b.clone()], Spacing::Gap(0), VAlign::Bottom);
+        assert_eq!(res.to_rows(), vec!["  e", "abf", "cdg"]);
+
+        let res = side_by_side(&[a, b], Spacing::Gap(0), VAlign::Baseline);
+        assert_eq!(res.to_rows(), vec!["abe", "cdf", "  g"]);
+        assert_eq!(res.baseline(), 2);
+    }
+
+    #[test]
+    fn vertical() {
+        let a = grid(&["abcd"], 1);
+        let b = grid(&["ef"], 1);
+
+        let res = stack(&[a, b], 1, HAlign::Center);
+        assert_eq!(res.to_rows(), vec!["abcd", "    ", " ef "]);
+    }
+
+    #[test]
+    fn smushing() {
+        let font = FIGfont::standard().unwrap();
+        let render = |text: &str| {
+            let mut figure = FIGure::new(&font, 80);
+            figure.add(text).unwrap();
+            figure.grid()
+        };
+
+        let res = side_by_side(
+            &[render("l"), render("l")],
+            Spacing::Smushing(font.header().layout()),
+            VAlign::Top,
+        );
+        assert_eq!(res.to_rows(), render("ll").to_rows());
+
+        let gap = side_by_side(&[render("l"), render("l")], Spacing::Gap(2), VAlign::Top);
+        assert!(gap.width() > res.width());
+    }
+}
@@ -0,0 +1,557 @@
+use std::cmp::Reverse;
+
+use encoding::{all::ISO_8859_1, DecoderTrap, Encoding};
+use figfont::{header::Layout, subcharacter::SubCharacter, FIGfont, PrintDirection};
+
+use crate::{font::FIGfontExt, line};
+
+const HORIZONTAL: Layout = Layout::from_bits_truncate(0xff);
+
+/// Text recognised in FIGlet art.
+#[derive(Debug, Clone)]
+pub struct Recognition<'a> {
+    /// The recognised text, one line per row of FIGcharacters.
+    pub text: String,
+    /// How much of the art the rendering of `text` reproduces, from 0 to 1.
+    pub confidence: f64,
+    /// The font that reproduces the art best.
+    pub font: &'a FIGfont,
+    /// The horizontal layout the art was rendered with.
+    pub layout: Layout,
+}
+
+/// Recovers text from its FIGlet rendering, by searching the characters
+/// whose glyphs, kerned and smushed together, reproduce it.
+pub struct Decoder<'a> {
+    fonts: Vec<&'a FIGfont>,
+    alphabet: Option<Vec<char>>,
+    beam: usize,
+}
+
+#[derive(Clone)]
+struct State {
+    text: String,
+    lines: Vec<Vec<SubCharacter>>,
+    cost: usize,
+    ink: usize,
+    blank: bool,
+}
+
+/// The cells of some art, mirrored for right-to-left fonts so that the text
+/// always starts on the left.
+struct Target {
+    rows: Vec<Vec<char>>,
+    width: usize,
+}
+
+#[inline]
+pub(crate) fn is_ink(ch: char) -> bool {
+    !ch.is_whitespace()
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new<'b>(font: &'b FIGfont) -> Decoder<'b> {
+        Decoder {
+            fonts: vec![font],
+            alphabet: None,
+            beam: 8,
+        }
+    }
+
+    /// Add a candidate font.
+    pub fn push(&mut self, font: &'a FIGfont) {
+        self.fonts.push(font);
+    }
+
+    pub fn fonts(&self) -> &[&'a FIGfont] {
+        &self.fonts[..]
+    }
+
+    /// Restrict the characters looked for. By default they are every
+    /// printable ASCII and Latin-1 character the font defines.
+    pub fn set_alphabet<I: IntoIterator<Item = char>>(&mut self, alphabet: I) {
+        self.alphabet = Some(alphabet.into_iter().collect());
+    }
+
+    pub fn clear_alphabet(&mut self) {
+        self.alphabet = None;
+    }
+
+    pub fn beam_width(&self) -> usize {
+        self.beam
+    }
+
+    /// Set how many partial guesses are kept at every step: wider beams are
+    /// slower but recover from more ambiguities.
+    pub fn set_beam_width(&mut self, beam: usize) {
+        self.beam = std::cmp::max(beam, 1);
+    }
+
+    /// Recognise `art`, returning the best guess. The search stops at the
+    /// first font and layout reproducing the art exactly.
+    pub fn decode(&self, art: &str) -> Option<Recognition<'a>> {
+        let mut best: Option<Recognition<'a>> = None;
+
+        for font in self.fonts.iter() {
+            for layout in layouts(font) {
+                if let Some(recognition) = self.decode_with(font, layout, art) {
+                    if recognition.confidence >= 1.0 {
+                        return Some(recognition);
+                    }
+                    if best
+                        .as_ref()
+                        .map_or(true, |best| recognition.confidence > best.confidence)
+                    {
+                        best = Some(recognition);
+                    }
+                }
+            }
+        }
+
+        best
+    }
+
+    /// Recognise `art` with every font and plausible layout, the best
+    /// guesses first.
+    pub fn candidates(&self, art: &str) -> Vec<Recognition<'a>> {
+        let mut res = Vec::new();
+
+        for font in self.fonts.iter() {
+            for layout in layouts(font) {
+                if let Some(recognition) = self.decode_with(font, layout, art) {
+                    res.push(recognition);
+                }
+            }
+        }
+
+        res.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+        res
+    }
+
+    fn alphabet(&self, font: &FIGfont) -> Vec<char> {
+        match self.alphabet {
+            Some(ref alphabet) => alphabet
+                .iter()
+                .copied()
+                .filter(|&ch| font.has_char(ch))
+                .collect(),
+            None => (' '..='~')
+                .chain('\u{a0}'..='\u{ff}')
+                .filter(|&ch| font.has_char(ch))
+                .collect(),
+        }
+    }
+
+    fn decode_with(&self, font: &'a FIGfont, layout: Layout, art: &str) -> Option<Recognition<'a>> {
+        let direction = font.header().print_direction();
+        let height = font.header().height();
+        let hardblank = ISO_8859_1
+            .decode(font.header().hard_blank_char(), DecoderTrap::Replace)
+            .ok()
+            .and_then(|s| s.chars().next());
+        let rows = normalize(art, direction);
+        if rows.is_empty() || height == 0 {
+            return None;
+        }
+
+        let alphabet: Vec<(char, bool)> = self
+            .alphabet(font)
+            .into_iter()
+            .map(|ch| {
+                (
+                    ch,
+                    font.get(ch as i32)
+                        .lines()
+                        .iter()
+                        .flatten()
+                        .any(is_ink_cell),
+                )
+            })
+            .collect();
+        let blocks = rows.len().div_ceil(height);
+        let mut best: Option<(Vec<String>, usize, usize)> = None;
+
+        // the art may start anywhere in the first row of FIGcharacters, when
+        // its blank rows were stripped
+        for pad in 0..=(blocks * height - rows.len()) {
+            let mut padded = vec![Vec::new(); pad];
+            padded.extend(rows.iter().cloned());
+            padded.resize(blocks * height, Vec::new());
+
+            let mut lines = Vec::new();
+            let (mut cost, mut ink) = (0, 0);
+            for block in padded.chunks(height) {
+                let target = Target::new(block, hardblank);
+                let state = self.decode_block(font, layout, &alphabet, &target, hardblank)?;
+                cost += state.cost;
+                ink += state.ink;
+                lines.push(state.text);
+            }
+
+            if best
+                .as_ref()
+                .map_or(true, |&(_, c, i)| score(cost, ink) > score(c, i))
+            {
+                best = Some((lines, cost, ink));
+            }
+        }
+
+        let (lines, cost, ink) = best?;
+        Some(Recognition {
+            text: lines.join("\n"),
+            confidence: score(cost, ink),
+            font,
+            layout,
+        })
+    }
+
+    fn decode_block(
+        &self,
+        font: &FIGfont,
+        layout: Layout,
+        alphabet: &[(char, bool)],
+        target: &Target,
+        hardblank: Option<char>,
+    ) -> Option<State> {
+        let direction = font.header().print_direction();
+        let smushing = layout.contains(Layout::HORIZONTAL_SMUSH);
+        let mut beam = vec![State {
+            text: String::new(),
+            lines: vec![Vec::new(); font.header().height()],
+            cost: 0,
+            ink: 0,
+            blank: true,
+        }];
+        let mut complete: Vec<State> = Vec::new();
+
+        if target.width == 0 {
+            return beam.pop();
+        }
+
+        while !beam.is_empty() {
+            let mut next = Vec::new();
+
+            for state in beam.iter() {
+                let width = state.lines.iter().map(Vec::len).max().unwrap_or(0);
+
+                for &(ch, visible) in alphabet {
+                    // blank glyphs only separate words, one is enough
+                    if !visible && state.blank {
+                        continue;
+                    }
+
+                    let mut lines = state.lines.clone();
+                    line::append_glyph(
+                        &mut lines,
+                        state.text.is_empty(),
+                        font.glyph(ch as i32).into_owned(),
+                        direction,
+                        layout,
+                    );
+                    let lines = oriented(lines, direction);
+                    let new_width = lines.iter().map(Vec::len).max().unwrap_or(0);
+                    if new_width <= width {
+                        continue;
+                    }
+
+                    let mut text = state.text.clone();
+                    text.push(ch);
+                    if ink_width(&lines) >= target.width + indent(&lines) {
+                        let (cost, ink) = target.compare(&lines, hardblank, None);
+                        complete.push(State {
+                            text,
+                            lines: Vec::new(),
+                            cost,
+                            ink,
+                            blank: false,
+                        });
+                    } else {
+                        let (cost, ink) = target.compare(&lines, hardblank, Some(smushing));
+                        next.push(State {
+                            text,
+                            lines: oriented(lines, direction),
+                            cost,
+                            ink,
+                            blank: !visible,
+                        });
+                    }
+                }
+            }
+
+            next.sort_by_key(|state| (state.cost, Reverse(state.ink)));
+            // keep a single guess for glyphs that render the same
+            let mut kept: Vec<State> = Vec::with_capacity(self.beam);
+            for state in next {
+                if kept.len() == self.beam {
+                    break;
+                }
+                if kept.iter().all(|other| other.lines != state.lines) {
+                    kept.push(state);
+                }
+            }
+            let mut next = kept;
+            // a guess already worse than a complete one can't improve
+            if let Some(cost) = complete.iter().map(|state| state.cost).min() {
+                next.retain(|state| state.cost < cost);
+            }
+            beam = next;
+        }
+
+        complete.sort_by(|a, b| {
+            score(b.cost, b.ink)
+                .total_cmp(&score(a.cost, a.ink))
+                .then(a.text.chars().count().cmp(&b.text.chars().count()))
+        });
+        complete.into_iter().next()
+    }
+}
+
+/// The layouts worth trying for `font`: its own, then the ones the art may
+/// have been rendered with overriding it.
+fn layouts(font: &FIGfont) -> Vec<Layout> {
+    let own = font.header().layout() & HORIZONTAL;
+    let mut res = vec![own];
+
+    for layout in [
+        Layout::HORIZONTAL_SMUSH | (own & !(Layout::HORIZONTAL_SMUSH | Layout::HORIZONTAL_KERNING)),
+        Layout::HORIZONTAL_SMUSH,
+        Layout::HORIZONTAL_KERNING,
+        Layout::empty(),
+    ] {
+        if !res.contains(&layout) {
+            res.push(layout);
+        }
+    }
+
+    res
+}
+
+#[inline]
+fn score(cost: usize, ink: usize) -> f64 {
+    if ink == 0 {
+        if cost == 0 {
+            1.0
+        } else {
+            0.0
+        }
+    } else {
+        1.0 - cost as f64 / ink as f64
+    }
+}
+
+/// Flip the rows of a right-to-left rendering, and back.
+fn oriented(
+    mut lines: Vec<Vec<SubCharacter>>,
+    direction: PrintDirection,
+) -> Vec<Vec<SubCharacter>> {
+    if let PrintDirection::RightToLeft = direction {
+        for line in lines.iter_mut() {
+            line.reverse();
+        }
+    }
+    lines
+}
+
+fn is_ink_cell(sch: &SubCharacter) -> bool {
+    match sch {
+        SubCharacter::Blank => false,
+        SubCharacter::Symbol(sym) => !sym.trim().is_empty(),
+    }
+}
+
+/// Get the blank columns before the ink, left by fonts that don't trim the
+/// first glyph.
+fn indent(lines: &[Vec<SubCharacter>]) -> usize {
+    lines
+        .iter()
+        .filter_map(|line| line.iter().position(is_ink_cell))
+        .min()
+        .unwrap_or(0)
+}
+
+fn ink_width(lines: &[Vec<SubCharacter>]) -> usize {
+    lines
+        .iter()
+        .map(|line| line.iter().rposition(is_ink_cell).map_or(0, |i| i + 1))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Split `art` in rows of cells, without the blank rows around it and the
+/// blank columns before it.
+pub(crate) fn normalize(art: &str, direction: PrintDirection) -> Vec<Vec<char>> {
+    let mut rows: Vec<Vec<char>> = art
+        .lines()
+        .map(|row| row.trim_end().chars().collect())
+        .collect();
+
+    while rows.last().is_some_and(Vec::is_empty) {
+        rows.pop();
+    }
+    let leading = rows.iter().take_while(|row| row.is_empty()).count();
+    rows.drain(..leading);
+
+    if let PrintDirection::RightToLeft = direction {
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        for row in rows.iter_mut() {
+            row.resize(width, ' ');
+            row.reverse();
+        }
+    }
+
+    let indent = rows
+        .iter()
+        .filter_map(|row| row.iter().position(|&ch| is_ink(ch)))
+        .min()
+        .unwrap_or(0);
+    for row in rows.iter_mut() {
+        let indent = std::cmp::min(indent, row.len());
+        row.drain(..indent);
+        while row.last().is_some_and(|&ch| !is_ink(ch)) {
+            row.pop();
+        }
+    }
+
+    rows
+}
+
+impl Target {
+    fn new(rows: &[Vec<char>], hardblank: Option<char>) -> Target {
+        let width = rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .rposition(|&ch| is_ink(ch) && Some(ch) != hardblank)
+                    .map_or(0, |i| i + 1)
+            })
+            .max()
+            .unwrap_or(0);
+
+        Target {
+            rows: rows.to_vec(),
+            width,
+        }
+    }
+
+    fn get(&self, y: usize, x: usize) -> char {
+        self.rows
+            .get(y)
+            .and_then(|row| row.get(x))
+            .copied()
+            .unwrap_or(' ')
+    }
+
+    /// Count the cells of `lines` that differ from the target, and the
+    /// cells with ink in either. A partial guess, with `smushing` known, is
+    /// compared leniently where the next character could still change it:
+    /// after the ink of every row, and on the last ink cell if it may be
+    /// smushed. The blank columns before the ink of `lines` are skipped,
+    /// like those before the art.
+    fn compare(
+        &self,
+        lines: &[Vec<SubCharacter>],
+        hardblank: Option<char>,
+        smushing: Option<bool>,
+    ) -> (usize, usize) {
+        let (mut cost, mut ink) = (0, 0);
+        let indent = indent(lines);
+        let width = match smushing {
+            Some(_) => 0,
+            None => self.width + indent,
+        };
+
+        for (y, line) in lines.iter().enumerate() {
+            let last = line.iter().rposition(is_ink_cell);
+
+            for x in indent..std::cmp::max(line.len(), width) {
+                let target = self.get(y, x - indent);
+                let target_ink = is_ink(target) && Some(target) != hardblank;
+                let tail = smushing.is_some() && last.map_or(true, |last| x > last);
+
+                match line.get(x) {
+                    Some(sch) if is_ink_cell(sch) => {
+                        ink += 1;
+                        let same = match sch {
+                            SubCharacter::Symbol(sym) => sym.chars().eq(std::iter::once(target)),
+                            SubCharacter::Blank => false,
+                        };
+                        let smushed = smushing == Some(true) && Some(x) == last && target_ink;
+                        if !same && !smushed {
+                            cost += 1;
+                        }
+                    }
+                    _ if target_ink && !tail => {
+                        ink += 1;
+                        cost += 1;
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        (cost, ink)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Decoder;
+    use crate::figure::FIGure;
+    use figfont::{header::Layout, FIGfont};
+
+    fn render(font: &FIGfont, text: &str) -> String {
+        let mut figure = FIGure::new(font, 200);
+        figure.add(text).unwrap();
+        figure.to_string()
+    }
+
+    #[test]
+    fn decode() {
+        let font = FIGfont::standard().unwrap();
+        let decoder = Decoder::new(&font);
+
+        let res = decoder.decode(&render(&font, "Hello, World!")).unwrap();
+        assert_eq!(res.text, "Hello, World!");
+        assert_eq!(res.confidence, 1.0);
+        assert_eq!(res.layout, font.header().layout() & super::HORIZONTAL);
+    }
+
+    #[test]
+    fn stripped_and_indented() {
+        let font = FIGfont::standard().unwrap();
+        let decoder = Decoder::new(&font);
+        let art: String = render(&font, "riglet 42")
+            .lines()
+            .filter(|row| !row.trim().is_empty())
+            .map(|row| format!("    {}\n", row))
+            .collect();
+
+        assert_eq!(decoder.decode(&art).unwrap().text, "riglet 42");
+    }
+
+    #[test]
+    fn full_width() {
+        let font = FIGfont::standard().unwrap();
+        let decoder = Decoder::new(&font);
+        let art: String = (0..font.header().height())
+            .map(|i| {
+                let mut row = String::new();
+                for ch in "Hi".chars() {
+                    for sch in font.get(ch as i32).lines()[i].iter() {
+                        row.push_str(&sch.to_string());
+                    }
+                }
+                row + "\n"
+            })
+            .collect();
+
+        // smushed with a space in between, "H i" renders the same
+        let res = decoder.candidates(&art);
+        let full = res
+            .iter()
+            .find(|res| res.layout == Layout::empty())
+            .unwrap();
+        assert_eq!(full.text, "Hi");
+        assert_eq!(full.confidence, 1.0);
+    }
+}
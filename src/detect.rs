@@ -0,0 +1,191 @@
+use std::collections::HashSet;
+
+use encoding::{all::ISO_8859_1, DecoderTrap, Encoding};
+use figfont::{subcharacter::SubCharacter, FIGfont};
+
+use crate::{
+    decode::{self, Decoder},
+    font::FIGfontExt,
+};
+
+/// How well a font matches some FIGlet art.
+#[derive(Debug, Clone)]
+pub struct FontMatch<'a> {
+    pub font: &'a FIGfont,
+    /// The overall score, from 0 to 1.
+    pub score: f64,
+    /// How well the art's height fits the font's.
+    pub height: f64,
+    /// How well the ink of the art's rows follows the font's.
+    pub rows: f64,
+    /// How many of the art's sub-characters the font uses.
+    pub subcharacters: f64,
+    /// The text recognised with the font, if the art could be decoded.
+    pub text: Option<String>,
+}
+
+/// Rank `fonts` by how well they match `art`, the best first.
+///
+/// Fonts are compared on cheap features first, the art's height, the ink
+/// of its rows and the sub-characters it uses, then the plausible ones
+/// decode the art and are scored on how well their rendering of the
+/// recognised text reproduces it. Fonts scoring less than half the best
+/// on the cheap features aren't decoded and score 0.
+pub fn detect_font<'a>(art: &str, fonts: &[&'a FIGfont]) -> Vec<FontMatch<'a>> {
+    let mut res: Vec<FontMatch<'a>> = fonts
+        .iter()
+        .map(|&font| {
+            let rows = decode::normalize(art, font.header().print_direction());
+            let height = height_score(&rows, font);
+            let profile = profile_score(&rows, font);
+            let subcharacters = subcharacter_score(&rows, font);
+
+            FontMatch {
+                font,
+                score: height * profile * subcharacters,
+                height,
+                rows: profile,
+                subcharacters,
+                text: None,
+            }
+        })
+        .collect();
+
+    let best = res.iter().map(|m| m.score).fold(0.0, f64::max);
+    for m in res.iter_mut() {
+        if m.score < best / 2.0 {
+            m.score = 0.0;
+            continue;
+        }
+
+        match Decoder::new(m.font).decode(art) {
+            Some(recognition) => {
+                m.score *= recognition.confidence;
+                m.text = Some(recognition.text);
+            }
+            None => m.score = 0.0,
+        }
+    }
+
+    res.sort_by(|a, b| b.score.total_cmp(&a.score));
+    res
+}
+
+/// The codes of the glyphs characterising a font.
+fn sample_codes() -> impl Iterator<Item = i32> {
+    33..127
+}
+
+fn symbols(font: &FIGfont) -> HashSet<String> {
+    sample_codes()
+        .flat_map(|code| font.glyph(code).into_owned())
+        .flatten()
+        .filter_map(|sch| match sch {
+            SubCharacter::Symbol(sym) if !sym.trim().is_empty() => Some(sym),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The art fills whole rows of FIGcharacters, less the blank rows that may
+/// have been stripped.
+fn height_score(rows: &[Vec<char>], font: &FIGfont) -> f64 {
+    let height = font.header().height();
+    if rows.is_empty() || height == 0 {
+        return 0.0;
+    }
+
+    let blocks = rows.len().div_ceil(height);
+    1.0 - (blocks * height - rows.len()) as f64 / height as f64
+}
+
+/// Compare the share of ink on every row of the art with the glyphs of the
+/// font, aligning the art on the best row.
+fn profile_score(rows: &[Vec<char>], font: &FIGfont) -> f64 {
+    let height = font.header().height();
+    if rows.is_empty() || height == 0 {
+        return 0.0;
+    }
+
+    let mut expected = vec![0.0; height];
+    for code in sample_codes() {
+        for (i, line) in font.glyph(code).iter().enumerate().take(height) {
+            expected[i] += line
+                .iter()
+                .filter(|sch| matches!(sch, SubCharacter::Symbol(sym) if !sym.trim().is_empty()))
+                .count() as f64;
+        }
+    }
+    normalize(&mut expected);
+
+    let blocks = rows.len().div_ceil(height);
+    (0..=(blocks * height - rows.len()))
+        .map(|pad| {
+            let mut actual = vec![0.0; height];
+            for (i, row) in rows.iter().enumerate() {
+                actual[(i + pad) % height] +=
+                    row.iter().filter(|&&ch| decode::is_ink(ch)).count() as f64;
+            }
+            normalize(&mut actual);
+
+            let distance: f64 = expected
+                .iter()
+                .zip(actual.iter())
+                .map(|(a, b)| (a - b).abs())
+                .sum();
+            1.0 - distance / 2.0
+        })
+        .fold(0.0, f64::max)
+}
+
+/// The share of the art's distinct sub-characters found in the font.
+fn subcharacter_score(rows: &[Vec<char>], font: &FIGfont) -> f64 {
+    let hardblank = ISO_8859_1
+        .decode(font.header().hard_blank_char(), DecoderTrap::Replace)
+        .ok()
+        .and_then(|s| s.chars().next());
+    let used: HashSet<String> = rows
+        .iter()
+        .flatten()
+        .filter(|&&ch| decode::is_ink(ch) && Some(ch) != hardblank)
+        .map(|ch| ch.to_string())
+        .collect();
+    if used.is_empty() {
+        return 0.0;
+    }
+
+    let symbols = symbols(font);
+    used.iter().filter(|sym| symbols.contains(*sym)).count() as f64 / used.len() as f64
+}
+
+fn normalize(values: &mut [f64]) {
+    let sum: f64 = values.iter().sum();
+    if sum > 0.0 {
+        for value in values.iter_mut() {
+            *value /= sum;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::detect_font;
+    use crate::figure::FIGure;
+    use figfont::FIGfont;
+
+    #[test]
+    fn detect() {
+        let standard = FIGfont::standard().unwrap();
+        let banner = FIGfont::load_from("fonts/plain/banner.flf").unwrap();
+        let slant = FIGfont::load_from("fonts/plain/slant.flf").unwrap();
+
+        let mut figure = FIGure::new(&slant, 80);
+        figure.add("riglet").unwrap();
+
+        let res = detect_font(&figure.to_string(), &[&standard, &banner, &slant]);
+        assert!(std::ptr::eq(res[0].font, &slant));
+        assert_eq!(res[0].text.as_deref(), Some("riglet"));
+        assert!(res[0].score > res[1].score);
+        assert_eq!(res[2].score, 0.0);
+    }
+}
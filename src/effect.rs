@@ -0,0 +1,387 @@
+use figfont::subcharacter::SubCharacter;
+
+use crate::grid::{self, Grid};
+
+/// A decoration computed on a rendered grid.
+///
+/// Effects work on cells, one per SubCharacter: a cell is "ink" when it
+/// renders something visible, hard blanks are never ink and are never
+/// painted over.
+pub trait Effect {
+    fn apply(&self, grid: &Grid) -> Grid;
+}
+
+/// A terminal colour, emitted as an ANSI escape sequence around the cells an
+/// effect paints.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Gray,
+    /// An entry of the 256 colours palette.
+    Fixed(u8),
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    fn code(self) -> String {
+        match self {
+            Color::Black => "30".to_string(),
+            Color::Red => "31".to_string(),
+            Color::Green => "32".to_string(),
+            Color::Yellow => "33".to_string(),
+            Color::Blue => "34".to_string(),
+            Color::Magenta => "35".to_string(),
+            Color::Cyan => "36".to_string(),
+            Color::White => "37".to_string(),
+            Color::Gray => "90".to_string(),
+            Color::Fixed(n) => format!("38;5;{}", n),
+            Color::Rgb(r, g, b) => format!("38;2;{};{};{}", r, g, b),
+        }
+    }
+
+    /// Wrap `text` in the escape sequences selecting this colour.
+    pub fn paint(self, text: &str) -> String {
+        format!("\x1b[{}m{}\x1b[0m", self.code(), text)
+    }
+}
+
+fn cell(character: &str, color: Option<Color>) -> SubCharacter {
+    match color {
+        Some(color) => SubCharacter::Symbol(color.paint(character)),
+        None => SubCharacter::Symbol(character.to_string()),
+    }
+}
+
+fn is_ink(sch: &SubCharacter) -> bool {
+    match sch {
+        SubCharacter::Blank => false,
+        SubCharacter::Symbol(sym) => !sym.trim().is_empty(),
+    }
+}
+
+fn is_space(sch: &SubCharacter) -> bool {
+    match sch {
+        SubCharacter::Blank => false,
+        SubCharacter::Symbol(sym) => sym.trim().is_empty(),
+    }
+}
+
+/// Get the cells of `grid`, with every row padded to the same length.
+fn cells(grid: &Grid) -> Vec<Vec<SubCharacter>> {
+    let len = grid.rows().iter().map(Vec::len).max().unwrap_or(0);
+    grid.rows()
+        .iter()
+        .map(|row| {
+            let mut row = row.clone();
+            row.resize(len, grid::space());
+            row
+        })
+        .collect()
+}
+
+/// A drop shadow, the ink of the grid repeated at an offset below the
+/// original.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shadow {
+    offset: (usize, usize),
+    character: String,
+    color: Option<Color>,
+}
+
+impl Default for Shadow {
+    fn default() -> Self {
+        Shadow {
+            offset: (1, 1),
+            character: "░".to_string(),
+            color: None,
+        }
+    }
+}
+
+impl Shadow {
+    pub fn new() -> Shadow {
+        Shadow::default()
+    }
+
+    /// Get the horizontal and vertical offset of the shadow, in cells.
+    pub fn offset(&self) -> (usize, usize) {
+        self.offset
+    }
+
+    pub fn set_offset(&mut self, x: usize, y: usize) {
+        self.offset = (x, y);
+    }
+
+    pub fn character(&self) -> &str {
+        &self.character
+    }
+
+    pub fn set_character<S: Into<String>>(&mut self, character: S) {
+        self.character = character.into();
+    }
+
+    pub fn color(&self) -> Option<Color> {
+        self.color
+    }
+
+    pub fn set_color(&mut self, color: Option<Color>) {
+        self.color = color;
+    }
+}
+
+impl Effect for Shadow {
+    fn apply(&self, grid: &Grid) -> Grid {
+        let src = cells(grid);
+        let (dx, dy) = self.offset;
+        let width = src.first().map_or(0, Vec::len);
+
+        let mut rows = src.clone();
+        for row in rows.iter_mut() {
+            row.resize(width + dx, grid::space());
+        }
+        rows.resize(src.len() + dy, vec![grid::space(); width + dx]);
+
+        for (y, row) in src.iter().enumerate() {
+            for (x, sch) in row.iter().enumerate() {
+                let target = &mut rows[y + dy][x + dx];
+                if is_ink(sch) && is_space(target) {
+                    *target = cell(&self.character, self.color);
+                }
+            }
+        }
+
+        Grid::new(rows, grid.baseline(), grid.hardblank())
+    }
+}
+
+/// An outline, drawn on the blank cells surrounding the ink of the grid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outline {
+    character: String,
+    color: Option<Color>,
+}
+
+impl Default for Outline {
+    fn default() -> Self {
+        Outline {
+            character: ".".to_string(),
+            color: None,
+        }
+    }
+}
+
+impl Outline {
+    pub fn new() -> Outline {
+        Outline::default()
+    }
+
+    pub fn character(&self) -> &str {
+        &self.character
+    }
+
+    pub fn set_character<S: Into<String>>(&mut self, character: S) {
+        self.character = character.into();
+    }
+
+    pub fn color(&self) -> Option<Color> {
+        self.color
+    }
+
+    pub fn set_color(&mut self, color: Option<Color>) {
+        self.color = color;
+    }
+}
+
+impl Effect for Outline {
+    fn apply(&self, grid: &Grid) -> Grid {
+        let src = cells(grid);
+        let height = src.len();
+        let width = src.first().map_or(0, Vec::len);
+        let ink = |y: usize, x: usize| {
+            // `y` and `x` are shifted by one, the outline grows on every side
+            (1..=height).contains(&y) && (1..=width).contains(&x) && is_ink(&src[y - 1][x - 1])
+        };
+
+        let mut rows = Vec::with_capacity(height + 2);
+        for y in 0..height + 2 {
+            let mut row = Vec::with_capacity(width + 2);
+            for x in 0..width + 2 {
+                let sch = if (1..=height).contains(&y) && (1..=width).contains(&x) {
+                    src[y - 1][x - 1].clone()
+                } else {
+                    grid::space()
+                };
+
+                let near = (y.saturating_sub(1)..=y + 1)
+                    .any(|ny| (x.saturating_sub(1)..=x + 1).any(|nx| ink(ny, nx)));
+                if is_space(&sch) && near {
+                    row.push(cell(&self.character, self.color));
+                } else {
+                    row.push(sch);
+                }
+            }
+            rows.push(row);
+        }
+
+        Grid::new(rows, grid.baseline() + 1, grid.hardblank())
+    }
+}
+
+/// Replaces the ink of the grid with a solid character.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fill {
+    character: String,
+    color: Option<Color>,
+    enclosed: bool,
+}
+
+impl Default for Fill {
+    fn default() -> Self {
+        Fill {
+            character: "█".to_string(),
+            color: None,
+            enclosed: false,
+        }
+    }
+}
+
+impl Fill {
+    pub fn new() -> Fill {
+        Fill::default()
+    }
+
+    pub fn character(&self) -> &str {
+        &self.character
+    }
+
+    pub fn set_character<S: Into<String>>(&mut self, character: S) {
+        self.character = character.into();
+    }
+
+    pub fn color(&self) -> Option<Color> {
+        self.color
+    }
+
+    pub fn set_color(&mut self, color: Option<Color>) {
+        self.color = color;
+    }
+
+    pub fn enclosed(&self) -> bool {
+        self.enclosed
+    }
+
+    /// Also fill the spaces enclosed by ink, like the counter of an `O`.
+    pub fn set_enclosed(&mut self, enclosed: bool) {
+        self.enclosed = enclosed;
+    }
+}
+
+impl Effect for Fill {
+    fn apply(&self, grid: &Grid) -> Grid {
+        let mut rows = cells(grid);
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+
+        // spaces reachable from the border without crossing ink
+        let mut outside = vec![vec![!self.enclosed; width]; height];
+        if self.enclosed {
+            let mut stack: Vec<(usize, usize)> = (0..height)
+                .flat_map(|y| [(y, 0), (y, width.saturating_sub(1))])
+                .chain((0..width).flat_map(|x| [(0, x), (height.saturating_sub(1), x)]))
+                .collect();
+            while let Some((y, x)) = stack.pop() {
+                if y >= height || x >= width || outside[y][x] || is_ink(&rows[y][x]) {
+                    continue;
+                }
+                outside[y][x] = true;
+                if y > 0 {
+                    stack.push((y - 1, x));
+                }
+                if x > 0 {
+                    stack.push((y, x - 1));
+                }
+                if y + 1 < height {
+                    stack.push((y + 1, x));
+                }
+                if x + 1 < width {
+                    stack.push((y, x + 1));
+                }
+            }
+        }
+
+        for (y, row) in rows.iter_mut().enumerate() {
+            for (x, sch) in row.iter_mut().enumerate() {
+                if is_ink(sch) || (is_space(sch) && !outside[y][x]) {
+                    *sch = cell(&self.character, self.color);
+                }
+            }
+        }
+
+        Grid::new(rows, grid.baseline(), grid.hardblank())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Color, Effect, Fill, Outline, Shadow};
+    use crate::grid::Grid;
+    use figfont::subcharacter::SubCharacter;
+
+    fn grid(rows: &[&str]) -> Grid {
+        let rows = rows
+            .iter()
+            .map(|row| {
+                row.chars()
+                    .map(|ch| match ch {
+                        '$' => SubCharacter::Blank,
+                        ch => SubCharacter::from(ch),
+                    })
+                    .collect()
+            })
+            .collect();
+        Grid::new(rows, 1, "$")
+    }
+
+    #[test]
+    fn shadow() {
+        let mut shadow = Shadow::new();
+        shadow.set_character("#");
+
+        assert_eq!(
+            shadow.apply(&grid(&["ab$", "c  "])).to_rows(),
+            vec!["ab$ ", "c## ", " #  "]
+        );
+
+        shadow.set_color(Some(Color::Gray));
+        let res = shadow.apply(&grid(&["a"]));
+        assert_eq!(res.width(), 2);
+        assert_eq!(res.to_rows()[1], " \x1b[90m#\x1b[0m");
+    }
+
+    #[test]
+    fn outline() {
+        assert_eq!(
+            Outline::new().apply(&grid(&["a ", "$ "])).to_rows(),
+            vec!["... ", ".a. ", ".$. ", "    "]
+        );
+    }
+
+    #[test]
+    fn fill() {
+        let mut fill = Fill::new();
+        fill.set_character("#");
+        let o = grid(&["/-\\", "| |", "\\_/"]);
+
+        assert_eq!(fill.apply(&o).to_rows(), vec!["###", "# #", "###"]);
+
+        fill.set_enclosed(true);
+        assert_eq!(fill.apply(&o).to_rows(), vec!["###", "###", "###"]);
+    }
+}
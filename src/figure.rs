@@ -3,23 +3,61 @@ use std::{
     fmt::{Display, Formatter},
 };
 
-use encoding::{all::ISO_8859_1, Encoding};
-use figfont::{FIGfont, PrintDirection};
+use figfont::{header::Layout, subcharacter::SubCharacter, FIGfont, PrintDirection};
 
-use crate::{line::FIGline, utils::SplitWords};
+use crate::{
+    chain::FontChain,
+    charmap::CharMap,
+    compose::HAlign,
+    grid::{self, Grid},
+    line::FIGline,
+    output::{HardBlank, Tabs, Trailing, Whitespace},
+    render::Span,
+    split::{WhitespaceSplitter, WordSplitter},
+    translit::Transliterator,
+};
+
+/// The code of the character used for characters no font can render.
+const REPLACEMENT_CODE: i32 = '?' as i32;
 
 pub struct FIGure<'a> {
     width: usize,
-    font: &'a FIGfont,
+    chain: FontChain<'a>,
     lines: Vec<FIGline<'a>>,
+    hardblank: HardBlank,
+    trailing: Trailing,
+    strip_blank_rows: bool,
+    transliterator: Option<Box<dyn Transliterator + 'a>>,
+    charmap: CharMap,
+    splitter: Box<dyn WordSplitter + 'a>,
+    tabs: Tabs,
+    whitespace: Whitespace,
+    layout: Option<Layout>,
+    justify: Option<HAlign>,
+    pending: Vec<String>,
 }
 
 impl<'a> FIGure<'a> {
     pub fn new<'b>(font: &'b FIGfont, width: usize) -> FIGure<'b> {
+        FIGure::with_chain(FontChain::new(font), width)
+    }
+
+    pub fn with_chain<'b>(chain: FontChain<'b>, width: usize) -> FIGure<'b> {
         FIGure {
             width,
-            font,
+            chain,
             lines: Vec::new(),
+            hardblank: HardBlank::default(),
+            trailing: Trailing::default(),
+            strip_blank_rows: false,
+            transliterator: None,
+            charmap: CharMap::default(),
+            splitter: Box::new(WhitespaceSplitter::default()),
+            tabs: Tabs::default(),
+            whitespace: Whitespace::default(),
+            layout: None,
+            justify: None,
+            pending: Vec::new(),
         }
     }
 
@@ -28,7 +66,173 @@ impl<'a> FIGure<'a> {
     }
 
     pub fn font(&self) -> &'a FIGfont {
-        &self.font
+        self.chain.primary()
+    }
+
+    pub fn chain(&self) -> &FontChain<'a> {
+        &self.chain
+    }
+
+    pub fn hardblank(&self) -> HardBlank {
+        self.hardblank
+    }
+
+    pub fn set_hardblank(&mut self, hardblank: HardBlank) {
+        self.hardblank = hardblank;
+        for line in self.lines.iter_mut() {
+            line.set_hardblank(hardblank);
+        }
+    }
+
+    pub fn trailing(&self) -> Trailing {
+        self.trailing
+    }
+
+    pub fn set_trailing(&mut self, trailing: Trailing) {
+        self.trailing = trailing;
+    }
+
+    pub fn strip_blank_rows(&self) -> bool {
+        self.strip_blank_rows
+    }
+
+    pub fn set_strip_blank_rows(&mut self, strip: bool) {
+        self.strip_blank_rows = strip;
+    }
+
+    pub fn set_transliterator<T: Transliterator + 'a>(&mut self, transliterator: T) {
+        self.transliterator = Some(Box::new(transliterator));
+    }
+
+    pub fn clear_transliterator(&mut self) {
+        self.transliterator = None;
+    }
+
+    pub fn charmap(&self) -> &CharMap {
+        &self.charmap
+    }
+
+    pub fn charmap_mut(&mut self) -> &mut CharMap {
+        &mut self.charmap
+    }
+
+    pub fn set_charmap(&mut self, charmap: CharMap) {
+        self.charmap = charmap;
+    }
+
+    pub fn set_splitter<S: WordSplitter + 'a>(&mut self, splitter: S) {
+        self.splitter = Box::new(splitter);
+    }
+
+    pub fn tabs(&self) -> Tabs {
+        self.tabs
+    }
+
+    pub fn set_tabs(&mut self, tabs: Tabs) {
+        self.tabs = tabs;
+    }
+
+    pub fn whitespace(&self) -> Whitespace {
+        self.whitespace
+    }
+
+    /// Set the whitespace policy. Unless it's `Whitespace::Preserve`, spaces
+    /// are held back until the next word shows whether the row wraps there,
+    /// and end the text as far as they fit on the row.
+    pub fn set_whitespace(&mut self, whitespace: Whitespace) {
+        self.whitespace = whitespace;
+    }
+
+    /// Get the layout glyphs are joined with, the font's unless set.
+    pub fn layout(&self) -> Layout {
+        self.layout.unwrap_or_else(|| self.font().header().layout())
+    }
+
+    /// Override the font's layout for the text added from now on.
+    pub fn set_layout(&mut self, layout: Layout) {
+        self.layout = Some(layout);
+    }
+
+    pub fn clear_layout(&mut self) {
+        self.layout = None;
+    }
+
+    /// Get the justification of rows within the width, following the
+    /// print direction unless set, like figlet.
+    pub fn justify(&self) -> HAlign {
+        self.justify
+            .unwrap_or(match self.font().header().print_direction() {
+                PrintDirection::LeftToRight => HAlign::Left,
+                PrintDirection::RightToLeft => HAlign::Right,
+            })
+    }
+
+    pub fn set_justify(&mut self, justify: HAlign) {
+        self.justify = Some(justify);
+    }
+
+    pub fn clear_justify(&mut self) {
+        self.justify = None;
+    }
+
+    /// Remove the text added so far, keeping every setting.
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.pending.clear();
+    }
+
+    fn fallback_code(ch: char) -> i32 {
+        if ch.is_whitespace() && !ch.is_control() {
+            ' ' as i32
+        } else if (ch as u32) < 256 {
+            ch as i32
+        } else {
+            REPLACEMENT_CODE
+        }
+    }
+
+    fn push_codes(&self, codes: &mut Vec<i32>, ch: char) {
+        let ch = self.charmap.map(ch);
+        if self.chain.has_glyph(ch as i32) {
+            codes.push(ch as i32);
+            return;
+        }
+        // WORD JOINER and ZERO WIDTH NO-BREAK SPACE only prevent a break
+        if ch == '\u{2060}' || ch == '\u{feff}' {
+            return;
+        }
+
+        if let Some(ref transliterator) = self.transliterator {
+            if let Some(replacement) = transliterator.transliterate(ch) {
+                for ch in replacement.chars() {
+                    if self.chain.has_glyph(ch as i32) {
+                        codes.push(ch as i32);
+                    } else {
+                        codes.push(Self::fallback_code(ch));
+                    }
+                }
+                return;
+            }
+        }
+
+        codes.push(Self::fallback_code(ch));
+    }
+
+    fn codes(&self, word: &str) -> Vec<i32> {
+        let mut codes = Vec::with_capacity(word.len());
+        for ch in word.chars() {
+            self.push_codes(&mut codes, ch);
+        }
+        codes
+    }
+
+    fn new_line(&self) -> FIGline<'a> {
+        let mut line = FIGline::with_chain(self.chain.clone());
+        line.set_hardblank(self.hardblank);
+        if let Some(layout) = self.layout {
+            line.set_layout(layout);
+        }
+        line
     }
 
     pub fn add_char(&mut self, ch: char) -> Result<(), Cow<str>> {
@@ -36,68 +240,250 @@ impl<'a> FIGure<'a> {
     }
 
     pub fn add<S: AsRef<str>>(&mut self, text: S) -> Result<(), Cow<str>> {
-        let text = text.as_ref();
-        let mut words: Vec<FIGline> = Vec::new();
-        for word in SplitWords::new(text) {
-            let chars = ISO_8859_1.encode(&word, encoding::EncoderTrap::Replace)?;
-            let mut line = FIGline::new(&self.font);
-
-            for c in chars {
-                let old_line = line.clone();
-                line.add_char(c as i32);
-
-                if line.width() > self.width() {
-                    words.push(old_line);
-                    line = FIGline::new(&self.font);
+        for word in self.splitter.split(text.as_ref()) {
+            // the splitter may leave tabs inside words
+            for (i, piece) in word.split('\t').enumerate() {
+                if i != 0 {
+                    self.add_tab();
+                }
+                if !piece.is_empty() {
+                    self.add_item(piece);
                 }
             }
+        }
 
-            if !line.is_empty() {
-                words.push(line);
+        Ok(())
+    }
+
+    fn add_tab(&mut self) {
+        match self.tabs {
+            Tabs::Spaces(n) => {
+                for _ in 0..n {
+                    self.add_item(" ");
+                }
+            }
+            Tabs::Stops(n) => {
+                self.pending.clear();
+                self.add_tab_stop(std::cmp::max(n, 1));
             }
         }
+    }
 
-        for word in words {
-            if self.lines.is_empty() {
+    fn add_item(&mut self, word: &str) {
+        match self.whitespace {
+            Whitespace::Preserve => {
+                for line in self.word_lines(word) {
+                    self.push_word(line);
+                }
+            }
+            _ if self.splitter.is_space(word) => {
+                if self.whitespace == Whitespace::Trim || self.pending.is_empty() {
+                    self.pending.push(word.to_string());
+                }
+            }
+            _ => self.push_after_pending(word),
+        }
+    }
+
+    /// Lay out `word` after the pending spaces, dropping them if it doesn't
+    /// fit on the current row.
+    fn push_after_pending(&mut self, word: &str) {
+        let pending = std::mem::take(&mut self.pending);
+        let mut lines = self.word_lines(word).into_iter();
+        let first = match lines.next() {
+            Some(first) => first,
+            None => return,
+        };
+
+        let mut candidate = match self.lines.last() {
+            Some(line) => line.clone(),
+            None => self.new_line(),
+        };
+        for space in pending.iter() {
+            for line in self.word_lines(space) {
+                candidate.add_line(&line);
+            }
+        }
+        candidate.add_line(&first);
+
+        if candidate.width() <= self.width {
+            self.lines.pop();
+            self.lines.push(candidate);
+        } else {
+            self.lines.push(first);
+        }
+
+        for line in lines {
+            self.push_word(line);
+        }
+    }
+
+    fn word_lines(&self, word: &str) -> Vec<FIGline<'a>> {
+        let mut res = Vec::new();
+        let codes = self.codes(word);
+        let mut line = self.new_line();
+
+        for c in codes {
+            let old_line = line.clone();
+            line.add_char(c);
+
+            if line.width() > self.width() {
+                res.push(old_line);
+                line = self.new_line();
+            }
+        }
+
+        if !line.is_empty() {
+            res.push(line);
+        }
+
+        res
+    }
+
+    fn push_word(&mut self, word: FIGline<'a>) {
+        if self.lines.is_empty() {
+            self.lines.push(word);
+        } else {
+            let l = self.lines.len();
+            let mut line = self.lines.remove(l - 1);
+            let old_line = line.clone();
+            line.add_line(&word);
+
+            if line.width() > self.width {
+                self.lines.push(old_line);
                 self.lines.push(word);
             } else {
-                let l = self.lines.len();
-                let mut line = self.lines.remove(l - 1);
-                let old_line = line.clone();
-                line.add_line(&word);
-
-                if line.width() > self.width {
-                    self.lines.push(old_line);
-                    self.lines.push(word);
-                } else {
-                    self.lines.push(line);
+                self.lines.push(line);
+            }
+        }
+    }
+
+    fn add_tab_stop(&mut self, stop: usize) {
+        let column = self.lines.last().map_or(0, FIGline::width);
+        let target = (column / stop + 1) * stop;
+
+        if target > self.width {
+            let line = self.new_line();
+            self.lines.push(line);
+        } else {
+            if self.lines.is_empty() {
+                let line = self.new_line();
+                self.lines.push(line);
+            }
+            self.lines.last_mut().unwrap().pad(target);
+        }
+    }
+
+    /// Get the lines with the spaces still pending at the end of the text,
+    /// those that fit: the end of the text is no wrap to drop them at.
+    fn finished_lines(&self) -> Cow<'_, [FIGline<'a>]> {
+        if self.pending.is_empty() {
+            return Cow::Borrowed(&self.lines);
+        }
+
+        let mut lines = self.lines.clone();
+        let mut last = lines.pop().unwrap_or_else(|| self.new_line());
+        for space in self.pending.iter() {
+            for line in self.word_lines(space) {
+                let old_last = last.clone();
+                last.add_line(&line);
+                if last.width() > self.width {
+                    last = old_last;
+                    break;
                 }
             }
         }
+        lines.push(last);
 
-        Ok(())
+        Cow::Owned(lines)
     }
-}
 
-impl<'a> Display for FIGure<'a> {
-    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
-        for line in self.lines.iter() {
+    /// Render the grid, with the output modes applied, and the spans of
+    /// the characters in it.
+    pub(crate) fn render(&self) -> (Grid, Vec<Span>) {
+        let blank = self.hardblank.replacement(self.font());
+        let justify = self.justify();
+        let mut rows = Vec::new();
+        let mut spans = Vec::new();
+
+        for line in self.finished_lines().iter().filter(|line| !line.is_empty()) {
+            let width = line.width();
+            let indent = match justify {
+                HAlign::Left => 0,
+                HAlign::Center => self.width.saturating_sub(width) / 2,
+                HAlign::Right => self.width.saturating_sub(width),
+            };
+
+            for (code, columns) in line.spans() {
+                spans.push(Span {
+                    code,
+                    row: rows.len(),
+                    height: line.height(),
+                    start: columns.start + indent,
+                    end: columns.end + indent,
+                });
+            }
+
             for line in line.lines().iter() {
-                match self.font.header().print_direction() {
-                    PrintDirection::RightToLeft => {
-                        let width: usize = line.iter().map(|c| c.width()).sum();
-                        for _ in 0..(self.width - width) {
-                            write!(fmt, " ")?;
-                        }
+                let mut row = Vec::with_capacity(std::cmp::max(line.len(), self.width));
+                row.extend(std::iter::repeat(grid::space()).take(indent));
+                row.extend(line.iter().cloned());
+
+                if let Trailing::Pad = self.trailing {
+                    let width: usize = row.iter().map(SubCharacter::width).sum();
+                    for _ in width..self.width {
+                        row.push(grid::space());
                     }
-                    _ => (),
-                }
-                for c in line.iter() {
-                    write!(fmt, "{}", c)?;
                 }
-                write!(fmt, "\n")?;
+
+                rows.push(row);
             }
         }
+
+        let mut grid = Grid::new(rows, self.font().header().baseline(), blank);
+        if let Trailing::Trim = self.trailing {
+            grid.trim();
+        }
+        if self.strip_blank_rows {
+            let leading = grid
+                .rows()
+                .iter()
+                .take_while(|row| grid.is_blank_row(row))
+                .count();
+            grid.strip_blank_rows();
+            // clip the spans to the rows left
+            let end = leading + grid.height();
+            for span in spans.iter_mut() {
+                let top = std::cmp::min(std::cmp::max(span.row, leading), end);
+                let bottom = std::cmp::max(std::cmp::min(span.row + span.height, end), top);
+                span.row = top - leading;
+                span.height = bottom - top;
+            }
+        }
+
+        (grid, spans)
+    }
+
+    /// Get the rendered grid, with the output modes applied.
+    pub fn grid(&self) -> Grid {
+        self.render().0
+    }
+
+    /// Get the characters laid out in the grid, with the columns they span.
+    pub fn spans(&self) -> Vec<Span> {
+        self.render().1
+    }
+
+    pub fn rows(&self) -> Vec<String> {
+        self.grid().to_rows()
+    }
+}
+
+impl<'a> Display for FIGure<'a> {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        for row in self.rows() {
+            writeln!(fmt, "{}", row)?;
+        }
         Ok(())
     }
 }
@@ -105,6 +491,10 @@ impl<'a> Display for FIGure<'a> {
 #[cfg(test)]
 mod tests {
     use super::FIGure;
+    use crate::{
+        BasicTransliterator, CharClass, CharMap, FontChain, HardBlank, Tabs, Trailing, Whitespace,
+        WhitespaceSplitter,
+    };
     use figfont::FIGfont;
 
     #[test]
@@ -118,4 +508,212 @@ mod tests {
 
         println!("{}", figure);
     }
+
+    #[test]
+    fn test_hardblank() {
+        let font = FIGfont::standard().unwrap();
+        let mut figure = FIGure::new(&font, 80);
+        figure.add("a b").unwrap();
+
+        assert!(!figure.to_string().contains('$'));
+
+        figure.set_hardblank(HardBlank::Marker);
+        assert!(figure.to_string().contains('$'));
+
+        figure.set_hardblank(HardBlank::NonBreakingSpace);
+        assert!(figure.to_string().contains('\u{a0}'));
+    }
+
+    #[test]
+    fn test_trailing() {
+        let font = FIGfont::standard().unwrap();
+        let mut figure = FIGure::new(&font, 40);
+        figure.add("Hi").unwrap();
+
+        figure.set_trailing(Trailing::Trim);
+        assert!(figure.rows().iter().all(|row| !row.ends_with(' ')));
+
+        figure.set_trailing(Trailing::Pad);
+        assert!(figure.rows().iter().all(|row| row.chars().count() == 40));
+    }
+
+    #[test]
+    fn test_strip_blank_rows() {
+        let font = FIGfont::standard().unwrap();
+        let mut figure = FIGure::new(&font, 80);
+        figure.add("Hi").unwrap();
+        assert_eq!(figure.rows().len(), 6);
+
+        figure.set_strip_blank_rows(true);
+        let rows = figure.rows();
+        assert_eq!(rows.len(), 5);
+        assert!(!rows.last().unwrap().trim().is_empty());
+    }
+
+    #[test]
+    fn test_chain() {
+        let font = FIGfont::load_from("fonts/plain/banner.flf").unwrap();
+        let fallback = FIGfont::standard().unwrap();
+        let mut chain = FontChain::new(&font);
+        chain.push(&fallback);
+
+        let mut figure = FIGure::with_chain(chain, 80);
+        figure.add("a\u{e9}").unwrap();
+        let rows = figure.rows();
+
+        assert_eq!(rows.len(), font.header().height());
+        assert!(rows.iter().all(|row| row.len() == rows[0].len()));
+    }
+
+    #[test]
+    fn test_transliterator() {
+        let font = FIGfont::standard().unwrap();
+        let render = |text: &str| {
+            let mut figure = FIGure::new(&font, 80);
+            figure.add(text).unwrap();
+            figure.to_string()
+        };
+
+        let mut figure = FIGure::new(&font, 80);
+        figure.add("\u{201c}ok\u{201d}").unwrap();
+        assert_eq!(figure.to_string(), render("?ok?"));
+
+        let mut figure = FIGure::new(&font, 80);
+        figure.set_transliterator(BasicTransliterator);
+        figure.add("\u{201c}ok\u{201d} \u{e9}").unwrap();
+        assert_eq!(figure.to_string(), render("\"ok\" \u{e9}"));
+    }
+
+    #[test]
+    fn test_charmap() {
+        let font = FIGfont::standard().unwrap();
+        let render = |text: &str, charmap: CharMap| {
+            let mut figure = FIGure::new(&font, 80);
+            figure.set_charmap(charmap);
+            figure.add(text).unwrap();
+            figure.to_string()
+        };
+
+        assert_eq!(
+            render("[x]~", CharMap::deutsch()),
+            render("\u{c4}x\u{dc}\u{df}", CharMap::new())
+        );
+
+        let mut figure = FIGure::new(&font, 80);
+        figure.charmap_mut().insert('\u{20ac}', 'E');
+        figure.add("5\u{20ac}").unwrap();
+        assert_eq!(figure.to_string(), render("5E", CharMap::new()));
+    }
+
+    #[test]
+    fn test_splitter() {
+        let font = FIGfont::standard().unwrap();
+        let mut first = FIGure::new(&font, 30);
+        first.add("aaa/").unwrap();
+
+        let mut figure = FIGure::new(&font, 30);
+        figure.set_splitter(WhitespaceSplitter::new(|ch| match ch {
+            '/' => CharClass::BreakAfter,
+            ch => crate::split::classify(ch),
+        }));
+        figure.add("aaa/bbb/ccc/ddd/eee").unwrap();
+
+        assert_eq!(figure.rows()[..6], first.rows()[..]);
+    }
+
+    #[test]
+    fn test_unicode_spaces() {
+        let font = FIGfont::standard().unwrap();
+        let render = |text: &str| {
+            let mut figure = FIGure::new(&font, 80);
+            figure.add(text).unwrap();
+            figure.to_string()
+        };
+
+        assert_eq!(render("a\u{2003}b"), render("a b"));
+        assert_eq!(render("a\u{2060}b\u{feff}"), render("ab"));
+    }
+
+    #[test]
+    fn test_tabs() {
+        let font = FIGfont::standard().unwrap();
+        let render = |text: &str, tabs: Tabs| {
+            let mut figure = FIGure::new(&font, 80);
+            figure.set_tabs(tabs);
+            figure.add(text).unwrap();
+            figure.rows()
+        };
+
+        assert_eq!(
+            render("a\tb", Tabs::default()),
+            render("a b", Tabs::default())
+        );
+        assert_eq!(
+            render("a\tb", Tabs::Spaces(3)),
+            render("a   b", Tabs::default())
+        );
+
+        for text in &["a\t", "abc\t", "abcdefg\t"] {
+            let rows = render(text, Tabs::Stops(8));
+            assert!(rows.iter().all(|row| row.chars().count() % 8 == 0));
+        }
+
+        let rows = render("abcdefghijkl\tx", Tabs::Stops(40));
+        assert_eq!(rows.len(), 12);
+
+        // the padding is neither drawn as a hard blank nor smushed
+        let mut figure = FIGure::new(&font, 80);
+        figure.set_hardblank(HardBlank::Marker);
+        figure.set_tabs(Tabs::Stops(8));
+        figure.add("a\t|").unwrap();
+        let rows = figure.rows();
+        assert!(rows.iter().all(|row| !row.contains('$')));
+        assert_eq!(rows[5], "        |_|");
+
+        // tabs inside the words of a splitter are expanded too
+        let mut figure = FIGure::new(&font, 80);
+        figure.set_splitter(|text: &str| vec![text.to_string()]);
+        figure.set_tabs(Tabs::Stops(8));
+        figure.add("a\tb\tc").unwrap();
+        assert_eq!(figure.rows(), render("a\tb\tc", Tabs::Stops(8)));
+    }
+
+    #[test]
+    fn test_whitespace() {
+        let font = FIGfont::standard().unwrap();
+        let render = |text: &str, width: usize, whitespace: Whitespace| {
+            let mut figure = FIGure::new(&font, width);
+            figure.set_whitespace(whitespace);
+            figure.add(text).unwrap();
+            figure.rows()
+        };
+
+        assert_eq!(
+            render("a   b", 80, Whitespace::Collapse),
+            render("a b", 80, Whitespace::Preserve)
+        );
+        assert_eq!(
+            render("a   b", 80, Whitespace::Trim),
+            render("a   b", 80, Whitespace::Preserve)
+        );
+
+        // spaces ending the text are no wrap
+        assert_eq!(
+            render("a  ", 80, Whitespace::Trim),
+            render("a  ", 80, Whitespace::Preserve)
+        );
+        assert_eq!(
+            render("a  ", 80, Whitespace::Collapse),
+            render("a ", 80, Whitespace::Preserve)
+        );
+        assert_ne!(
+            render("a  ", 80, Whitespace::Preserve),
+            render("a", 80, Whitespace::Preserve)
+        );
+
+        let wrapped = render("Ciao ciao", 30, Whitespace::Trim);
+        let mut expected = render("Ciao", 30, Whitespace::Preserve);
+        expected.extend(render("ciao", 30, Whitespace::Preserve));
+        assert_eq!(wrapped, expected);
+    }
 }
@@ -0,0 +1,133 @@
+use std::borrow::Cow;
+
+use figfont::{subcharacter::SubCharacter, FIGfont};
+
+/// The code of the character FIGfont falls back to when a glyph is missing.
+const MISSING_CODE: i32 = 126;
+
+/// Extra queries on a loaded FIGfont.
+pub trait FIGfontExt {
+    /// Check if the font defines its own glyph for `code` instead of falling
+    /// back to the missing character glyph.
+    fn has_glyph(&self, code: i32) -> bool;
+
+    /// Check if `ch` can be rendered with its own glyph.
+    fn has_char(&self, ch: char) -> bool {
+        (ch as u32) < 256 && self.has_glyph(ch as i32)
+    }
+
+    /// Get the glyph for `code`, like `FIGfont::get`, with the
+    /// sub-characters written in UTF-8 decoded.
+    ///
+    /// The loader reads fonts as ISO-8859-1, so a `█` in a UTF-8 font is
+    /// read as three sub-characters, `â`, `\u{96}` and `\u{88}`: runs of
+    /// sub-characters forming a valid UTF-8 sequence are merged back.
+    fn glyph(&self, code: i32) -> Cow<'_, Vec<Vec<SubCharacter>>>;
+}
+
+fn single_byte(sch: &SubCharacter) -> Option<u8> {
+    match sch {
+        SubCharacter::Symbol(sym) => {
+            let mut chars = sym.chars();
+            match (chars.next(), chars.next()) {
+                (Some(ch), None) if (ch as u32) < 256 => Some(ch as u8),
+                _ => None,
+            }
+        }
+        SubCharacter::Blank => None,
+    }
+}
+
+fn utf8_len(lead: u8) -> usize {
+    match lead {
+        0xc2..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf4 => 4,
+        _ => 1,
+    }
+}
+
+fn decode_utf8(line: &[SubCharacter]) -> Vec<SubCharacter> {
+    let mut res = Vec::with_capacity(line.len());
+    let mut i = 0;
+
+    while i < line.len() {
+        let len = single_byte(&line[i]).map_or(1, utf8_len);
+        if len > 1 && i + len <= line.len() {
+            let bytes: Option<Vec<u8>> = line[i..i + len].iter().map(single_byte).collect();
+            if let Some(Ok(sym)) = bytes.map(String::from_utf8) {
+                res.push(SubCharacter::Symbol(sym));
+                i += len;
+                continue;
+            }
+        }
+
+        res.push(line[i].clone());
+        i += 1;
+    }
+
+    res
+}
+
+impl FIGfontExt for FIGfont {
+    fn has_glyph(&self, code: i32) -> bool {
+        code == MISSING_CODE || !std::ptr::eq(self.get(code), self.get(MISSING_CODE))
+    }
+
+    fn glyph(&self, code: i32) -> Cow<'_, Vec<Vec<SubCharacter>>> {
+        let lines = self.get(code).lines();
+        let encoded = lines
+            .iter()
+            .flatten()
+            .any(|sch| single_byte(sch).is_some_and(|byte| utf8_len(byte) > 1));
+
+        if encoded {
+            // the loader pads rows to the longest one in bytes, which is
+            // wider than the others once decoded
+            let mut lines: Vec<Vec<SubCharacter>> =
+                lines.iter().map(|line| decode_utf8(line)).collect();
+            let width = lines.iter().map(Vec::len).min().unwrap_or(0);
+            for line in lines.iter_mut() {
+                line.truncate(width);
+            }
+            Cow::Owned(lines)
+        } else {
+            lines
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FIGfontExt;
+    use figfont::{subcharacter::SubCharacter, FIGfont};
+
+    #[test]
+    fn has_glyph() {
+        let font = FIGfont::standard().unwrap();
+
+        assert!(font.has_glyph('A' as i32));
+        assert!(font.has_glyph('~' as i32));
+        assert!(font.has_char('é'));
+        assert!(!font.has_glyph(9));
+        assert!(!font.has_char('€'));
+    }
+
+    #[test]
+    fn utf8() {
+        let line: Vec<SubCharacter> = "a\u{e2}\u{96}\u{88}\u{e2}b"
+            .chars()
+            .map(SubCharacter::from)
+            .collect();
+
+        assert_eq!(
+            super::decode_utf8(&line),
+            vec![
+                SubCharacter::from('a'),
+                SubCharacter::from('█'),
+                SubCharacter::from('\u{e2}'),
+                SubCharacter::from('b'),
+            ]
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use std::sync::OnceLock;
+
+use figfont::FIGfont;
+
+fn parse(raw: &'static [u8]) -> FIGfont {
+    FIGfont::read_from(raw).expect("bundled fonts are valid")
+}
+
+macro_rules! bundled_fonts {
+    ($($name:ident),* $(,)?) => {
+        /// Names of all the bundled fonts.
+        pub const NAMES: &[&str] = &[$(stringify!($name)),*];
+
+        $(
+            #[doc = concat!("Get the bundled `", stringify!($name), "` font, parsed on first use.")]
+            pub fn $name() -> &'static FIGfont {
+                static FONT: OnceLock<FIGfont> = OnceLock::new();
+                FONT.get_or_init(|| {
+                    parse(include_bytes!(concat!(
+                        "../fonts/plain/",
+                        stringify!($name),
+                        ".flf"
+                    )))
+                })
+            }
+        )*
+
+        /// Get a bundled font by its name, parsed on first use.
+        pub fn by_name(name: &str) -> Option<&'static FIGfont> {
+            match name {
+                $(stringify!($name) => Some($name()),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+bundled_fonts!(
+    banner, big, block, bubble, digital, ivrit, lean, mini, mnemonic, script, shadow, slant, small,
+    smscript, smshadow, smslant, standard, term,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::{by_name, NAMES};
+
+    #[test]
+    fn all_fonts() {
+        for name in NAMES {
+            let font = by_name(name).unwrap();
+            assert!(std::ptr::eq(font, by_name(name).unwrap()));
+        }
+
+        assert!(by_name("missing").is_none());
+    }
+
+    #[test]
+    fn slant() {
+        assert_eq!(super::slant().header().height(), 6);
+    }
+}
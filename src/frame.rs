@@ -0,0 +1,249 @@
+use figfont::subcharacter::SubCharacter;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+    compose::HAlign,
+    effect::Effect,
+    grid::{self, Grid},
+};
+
+/// Border style of a frame.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum BorderStyle {
+    /// `+`, `-` and `|`.
+    Ascii,
+    #[default]
+    Single,
+    Double,
+    Rounded,
+    Heavy,
+}
+
+struct Border {
+    top_left: char,
+    top_right: char,
+    bottom_left: char,
+    bottom_right: char,
+    horizontal: char,
+    vertical: char,
+}
+
+impl BorderStyle {
+    fn border(self) -> Border {
+        let (top_left, top_right, bottom_left, bottom_right, horizontal, vertical) = match self {
+            BorderStyle::Ascii => ('+', '+', '+', '+', '-', '|'),
+            BorderStyle::Single => ('┌', '┐', '└', '┘', '─', '│'),
+            BorderStyle::Double => ('╔', '╗', '╚', '╝', '═', '║'),
+            BorderStyle::Rounded => ('╭', '╮', '╰', '╯', '─', '│'),
+            BorderStyle::Heavy => ('┏', '┓', '┗', '┛', '━', '┃'),
+        };
+
+        Border {
+            top_left,
+            top_right,
+            bottom_left,
+            bottom_right,
+            horizontal,
+            vertical,
+        }
+    }
+}
+
+/// A frame drawn around a rendered grid.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Frame {
+    style: BorderStyle,
+    padding: (usize, usize),
+    title: Option<String>,
+    title_align: HAlign,
+    width: Option<usize>,
+    align: HAlign,
+}
+
+impl Frame {
+    pub fn new(style: BorderStyle) -> Frame {
+        Frame {
+            style,
+            ..Frame::default()
+        }
+    }
+
+    pub fn style(&self) -> BorderStyle {
+        self.style
+    }
+
+    pub fn set_style(&mut self, style: BorderStyle) {
+        self.style = style;
+    }
+
+    /// Get the horizontal and vertical inner padding.
+    pub fn padding(&self) -> (usize, usize) {
+        self.padding
+    }
+
+    pub fn set_padding(&mut self, horizontal: usize, vertical: usize) {
+        self.padding = (horizontal, vertical);
+    }
+
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    /// Set the title drawn in the top border, and its alignment.
+    pub fn set_title<S: Into<String>>(&mut self, title: S, align: HAlign) {
+        self.title = Some(title.into());
+        self.title_align = align;
+    }
+
+    pub fn clear_title(&mut self) {
+        self.title = None;
+    }
+
+    pub fn width(&self) -> Option<usize> {
+        self.width
+    }
+
+    /// Set the outer width of the frame, borders included, and how the
+    /// content is aligned inside it. The frame still grows to fit wider
+    /// content.
+    pub fn set_width(&mut self, width: usize, align: HAlign) {
+        self.width = Some(width);
+        self.align = align;
+    }
+
+    pub fn clear_width(&mut self) {
+        self.width = None;
+    }
+
+    fn top(&self, border: &Border, inner: usize) -> Vec<SubCharacter> {
+        let mut row = vec![SubCharacter::from(border.top_left)];
+        let mut line = vec![SubCharacter::from(border.horizontal); inner];
+
+        if let Some(ref title) = self.title {
+            let available = inner.saturating_sub(2);
+            let mut title_cells = Vec::new();
+            let mut width = 0;
+            for grapheme in title.graphemes(true) {
+                let sch = SubCharacter::Symbol(grapheme.to_string());
+                if width + sch.width() > available {
+                    break;
+                }
+                width += sch.width();
+                title_cells.push(sch);
+            }
+
+            if width > 0 {
+                let mut cells = vec![grid::space()];
+                cells.extend(title_cells);
+                cells.push(grid::space());
+                let start = match self.title_align {
+                    HAlign::Left => std::cmp::min(1, inner - (width + 2)),
+                    HAlign::Center => (inner - (width + 2)) / 2,
+                    HAlign::Right => (inner - (width + 2)).saturating_sub(1),
+                };
+                // the border cells are one column wide each
+                line.splice(start..start + width + 2, cells);
+            }
+        }
+
+        row.extend(line);
+        row.push(SubCharacter::from(border.top_right));
+        row
+    }
+
+    /// Draw the frame around `grid`.
+    pub fn apply(&self, grid: &Grid) -> Grid {
+        let border = self.style.border();
+        let (hpad, vpad) = self.padding;
+        let content = grid.width();
+        let inner = std::cmp::max(
+            content + 2 * hpad,
+            self.width.map_or(0, |width| width.saturating_sub(2)),
+        );
+        let indent = match self.align {
+            HAlign::Left => hpad,
+            HAlign::Center => (inner - content) / 2,
+            HAlign::Right => inner - content - hpad,
+        };
+
+        let side = |cells: Vec<SubCharacter>| {
+            let mut row = vec![SubCharacter::from(border.vertical)];
+            let fill = inner.saturating_sub(grid::row_width(&cells));
+            row.extend(cells);
+            row.extend(std::iter::repeat(grid::space()).take(fill));
+            row.push(SubCharacter::from(border.vertical));
+            row
+        };
+
+        let mut rows = Vec::with_capacity(grid.height() + 2 * vpad + 2);
+        rows.push(self.top(&border, inner));
+        for _ in 0..vpad {
+            rows.push(side(Vec::new()));
+        }
+        for row in grid.rows() {
+            let mut cells = vec![grid::space(); indent];
+            cells.extend(row.iter().cloned());
+            rows.push(side(cells));
+        }
+        for _ in 0..vpad {
+            rows.push(side(Vec::new()));
+        }
+
+        let mut bottom = vec![SubCharacter::from(border.bottom_left)];
+        bottom.extend(std::iter::repeat(SubCharacter::from(border.horizontal)).take(inner));
+        bottom.push(SubCharacter::from(border.bottom_right));
+        rows.push(bottom);
+
+        Grid::new(rows, grid.baseline() + vpad + 1, grid.hardblank())
+    }
+}
+
+impl Effect for Frame {
+    fn apply(&self, grid: &Grid) -> Grid {
+        Frame::apply(self, grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BorderStyle, Frame};
+    use crate::{compose::HAlign, grid::Grid};
+    use figfont::subcharacter::SubCharacter;
+
+    fn grid(rows: &[&str]) -> Grid {
+        let rows = rows
+            .iter()
+            .map(|row| row.chars().map(SubCharacter::from).collect())
+            .collect();
+        Grid::new(rows, 1, " ")
+    }
+
+    #[test]
+    fn ascii() {
+        let mut frame = Frame::new(BorderStyle::Ascii);
+        frame.set_padding(1, 0);
+
+        assert_eq!(
+            frame.apply(&grid(&["ab", "c"])).to_rows(),
+            vec!["+----+", "| ab |", "| c  |", "+----+"]
+        );
+    }
+
+    #[test]
+    fn title_and_width() {
+        let mut frame = Frame::new(BorderStyle::Rounded);
+        frame.set_title("Hi", HAlign::Left);
+        frame.set_width(10, HAlign::Center);
+
+        assert_eq!(
+            frame.apply(&grid(&["ab"])).to_rows(),
+            vec!["╭─ Hi ───╮", "│   ab   │", "╰────────╯"]
+        );
+
+        frame.set_title("日本e\u{301}", HAlign::Left);
+        assert_eq!(
+            frame.apply(&grid(&["ab"])).to_rows()[0],
+            "╭─ 日本e\u{301} ╮"
+        );
+    }
+}
@@ -0,0 +1,202 @@
+use std::fmt::{Display, Formatter};
+
+use figfont::subcharacter::SubCharacter;
+
+use crate::output;
+
+/// A rendered block of SubCharacters, ready to be displayed, composed or
+/// decorated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid {
+    rows: Vec<Vec<SubCharacter>>,
+    baseline: usize,
+    hardblank: String,
+}
+
+#[inline]
+pub(crate) fn space() -> SubCharacter {
+    SubCharacter::Symbol(" ".to_string())
+}
+
+/// Get the width of a cell, ignoring the ANSI escape sequences colouring it.
+pub(crate) fn cell_width(sch: &SubCharacter) -> usize {
+    match sch {
+        SubCharacter::Symbol(sym) if sym.contains('\x1b') => {
+            let mut visible = String::with_capacity(sym.len());
+            let mut chars = sym.chars();
+            while let Some(ch) = chars.next() {
+                if ch == '\x1b' {
+                    // skip a CSI sequence up to its final byte
+                    if chars.next() == Some('[') {
+                        for ch in chars.by_ref() {
+                            if ('\x40'..='\x7e').contains(&ch) {
+                                break;
+                            }
+                        }
+                    }
+                } else {
+                    visible.push(ch);
+                }
+            }
+            SubCharacter::Symbol(visible).width()
+        }
+        sch => sch.width(),
+    }
+}
+
+#[inline]
+pub(crate) fn row_width(row: &[SubCharacter]) -> usize {
+    row.iter().map(cell_width).sum()
+}
+
+impl Grid {
+    /// Create a grid from its rows. `baseline` counts the rows from the top
+    /// down to the baseline, like in a FIGfont header, `hardblank` is what
+    /// hard blanks are rendered to.
+    pub fn new<S: Into<String>>(
+        rows: Vec<Vec<SubCharacter>>,
+        baseline: usize,
+        hardblank: S,
+    ) -> Grid {
+        Grid {
+            rows,
+            baseline,
+            hardblank: hardblank.into(),
+        }
+    }
+
+    pub fn rows(&self) -> &[Vec<SubCharacter>] {
+        &self.rows[..]
+    }
+
+    pub fn rows_mut(&mut self) -> &mut Vec<Vec<SubCharacter>> {
+        &mut self.rows
+    }
+
+    pub fn into_rows(self) -> Vec<Vec<SubCharacter>> {
+        self.rows
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Get the width (number of terminal cells) of the widest row.
+    pub fn width(&self) -> usize {
+        self.rows
+            .iter()
+            .map(|row| row_width(row))
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn baseline(&self) -> usize {
+        self.baseline
+    }
+
+    pub fn set_baseline(&mut self, baseline: usize) {
+        self.baseline = baseline;
+    }
+
+    pub fn hardblank(&self) -> &str {
+        &self.hardblank
+    }
+
+    pub fn set_hardblank<S: Into<String>>(&mut self, hardblank: S) {
+        self.hardblank = hardblank.into();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.iter().all(Vec::is_empty)
+    }
+
+    /// Pad every row with spaces up to `width` cells.
+    pub fn pad(&mut self, width: usize) {
+        for row in self.rows.iter_mut() {
+            let fill = width.saturating_sub(row_width(row));
+            row.extend(std::iter::repeat(space()).take(fill));
+        }
+    }
+
+    /// Render a single row.
+    pub fn render_row(&self, row: &[SubCharacter]) -> String {
+        row.iter()
+            .map(|c| output::render(c, &self.hardblank))
+            .collect()
+    }
+
+    /// Render every row.
+    pub fn to_rows(&self) -> Vec<String> {
+        self.rows.iter().map(|row| self.render_row(row)).collect()
+    }
+
+    pub(crate) fn is_blank_row(&self, row: &[SubCharacter]) -> bool {
+        output::is_blank_row(&self.render_row(row))
+    }
+
+    /// Remove the blank rows at the top and at the bottom.
+    pub fn strip_blank_rows(&mut self) {
+        while self.rows.last().is_some_and(|row| self.is_blank_row(row)) {
+            self.rows.pop();
+        }
+        let leading = self
+            .rows
+            .iter()
+            .take_while(|row| self.is_blank_row(row))
+            .count();
+        self.rows.drain(..leading);
+        self.baseline = self.baseline.saturating_sub(leading);
+    }
+
+    /// Remove the trailing cells rendered as spaces from every row.
+    pub fn trim(&mut self) {
+        let hardblank = &self.hardblank;
+        for row in self.rows.iter_mut() {
+            while row
+                .last()
+                .is_some_and(|c| output::render(c, hardblank) == " ")
+            {
+                row.pop();
+            }
+        }
+    }
+}
+
+impl Display for Grid {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        for row in self.rows.iter() {
+            writeln!(fmt, "{}", self.render_row(row))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+    use figfont::subcharacter::SubCharacter;
+
+    fn row(s: &str) -> Vec<SubCharacter> {
+        s.chars().map(SubCharacter::from).collect()
+    }
+
+    #[test]
+    fn grid() {
+        let mut grid = Grid::new(vec![row("   "), row("ab "), row("   ")], 2, "$");
+        grid.rows_mut()[1].push(SubCharacter::Blank);
+
+        assert_eq!(grid.width(), 4);
+        assert_eq!(grid.to_string(), "   \nab $\n   \n");
+
+        grid.strip_blank_rows();
+        assert_eq!(grid.height(), 1);
+        assert_eq!(grid.baseline(), 1);
+
+        grid.set_hardblank(" ");
+        grid.trim();
+        assert_eq!(grid.to_rows(), vec!["ab"]);
+
+        grid.pad(5);
+        assert_eq!(grid.to_rows(), vec!["ab   "]);
+    }
+}
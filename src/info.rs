@@ -0,0 +1,286 @@
+use std::{
+    fmt::{Display, Formatter},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use encoding::{all::ISO_8859_1, DecoderTrap, Encoding};
+use figfont::{header::Layout, FIGfont, PrintDirection};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+const VERTICAL_SMUSH: u32 = 16384;
+
+const HORIZONTAL_RULES: &[(Layout, &str)] = &[
+    (Layout::HORIZONTAL_EQUAL, "equal"),
+    (Layout::HORIZONTAL_LOWLINE, "lowline"),
+    (Layout::HORIZONTAL_HIERARCHY, "hierarchy"),
+    (Layout::HORIZONTAL_PAIR, "pair"),
+    (Layout::HORIZONTAL_BIGX, "bigx"),
+    (Layout::HORIZONTAL_HARDBLANK, "hardblank"),
+];
+
+const VERTICAL_RULES: &[(Layout, &str)] = &[
+    (Layout::VERTICAL_EQUAL, "equal"),
+    (Layout::VERTICAL_LOWLINE, "lowline"),
+    (Layout::VERTICAL_HIERARCHY, "hierarchy"),
+    (Layout::VERTICAL_PAIR, "pair"),
+    (Layout::VERTICAL_BIGX, "bigx"),
+];
+
+/// A summary of a FIGfont's header, like figlet's `-I` info codes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FontInfo {
+    /// The font's name, when known.
+    pub name: Option<String>,
+    /// The directory the font was loaded from, when known.
+    pub directory: Option<PathBuf>,
+    /// The format and revision of the header signature, always "flf2a" as
+    /// figfont reads no other.
+    pub format: String,
+    pub hardblank: String,
+    pub height: usize,
+    pub baseline: usize,
+    pub max_length: usize,
+    /// The old layout as in the header when read from a file, otherwise
+    /// implied by the full layout.
+    pub old_layout: i32,
+    pub full_layout: u32,
+    /// Print direction as in the header: 0 left-to-right, 1 right-to-left.
+    pub print_direction: u8,
+    pub comment_lines: usize,
+    pub codetag_count: Option<u32>,
+    /// Human-readable description of the horizontal layout.
+    pub horizontal_layout: String,
+    /// Human-readable description of the vertical layout.
+    pub vertical_layout: String,
+}
+
+fn describe(smush: bool, kerning: bool, layout: Layout, rules: &[(Layout, &str)]) -> String {
+    if smush {
+        let names: Vec<&str> = rules
+            .iter()
+            .filter(|(rule, _)| layout.contains(*rule))
+            .map(|(_, name)| *name)
+            .collect();
+
+        if names.is_empty() {
+            "smushing: universal".to_string()
+        } else {
+            format!("smushing: {}", names.join(", "))
+        }
+    } else if kerning {
+        "kerning".to_string()
+    } else {
+        "full width".to_string()
+    }
+}
+
+/// Describe the horizontal part of a layout, e.g. "smushing: equal, lowline".
+pub fn describe_horizontal_layout(layout: Layout) -> String {
+    describe(
+        layout.contains(Layout::HORIZONTAL_SMUSH),
+        layout.contains(Layout::HORIZONTAL_KERNING),
+        layout,
+        HORIZONTAL_RULES,
+    )
+}
+
+/// Describe the vertical part of a layout, e.g. "kerning".
+pub fn describe_vertical_layout(layout: Layout) -> String {
+    describe(
+        layout.bits() & VERTICAL_SMUSH != 0,
+        layout.contains(Layout::VERTICAL_KERNING),
+        layout,
+        VERTICAL_RULES,
+    )
+}
+
+pub(crate) fn old_layout(layout: Layout) -> i32 {
+    if layout.contains(Layout::HORIZONTAL_SMUSH) {
+        (layout.bits() & 63) as i32
+    } else if layout.contains(Layout::HORIZONTAL_KERNING) {
+        0
+    } else {
+        -1
+    }
+}
+
+/// The signature of the only FIGfont format and revision figfont reads.
+const SIGNATURE: &[u8] = b"flf2a";
+
+/// Read the old layout from the header line of a plain font file, splitting
+/// the arguments like figfont does.
+fn header_old_layout(bytes: &[u8]) -> Option<i32> {
+    let line = bytes.split(|&b| b == b'\n').next()?;
+    if !line.starts_with(SIGNATURE) {
+        return None;
+    }
+    // the hard blank, height, baseline, max length and old layout
+    let arg = line[SIGNATURE.len()..]
+        .split(|&b| b == b' ')
+        .enumerate()
+        .filter(|(i, arg)| *i == 0 || !arg.is_empty())
+        .map(|(_, arg)| arg)
+        .nth(4)?;
+    String::from_utf8_lossy(arg).trim().parse().ok()
+}
+
+impl FontInfo {
+    pub fn new(font: &FIGfont) -> FontInfo {
+        let header = font.header();
+        let layout = header.layout();
+
+        FontInfo {
+            name: None,
+            directory: None,
+            format: String::from_utf8_lossy(SIGNATURE).into_owned(),
+            hardblank: ISO_8859_1
+                .decode(header.hard_blank_char(), DecoderTrap::Replace)
+                .unwrap_or_default(),
+            height: header.height(),
+            baseline: header.baseline(),
+            max_length: header.max_length(),
+            old_layout: old_layout(layout),
+            full_layout: layout.bits(),
+            print_direction: match header.print_direction() {
+                PrintDirection::LeftToRight => 0,
+                PrintDirection::RightToLeft => 1,
+            },
+            comment_lines: header.comment().split('\n').count(),
+            codetag_count: header.codetag_count(),
+            horizontal_layout: describe_horizontal_layout(layout),
+            vertical_layout: describe_vertical_layout(layout),
+        }
+    }
+
+    /// Parse a plain font and summarize it, with the old layout of its
+    /// header.
+    pub fn read_from(bytes: &[u8]) -> figfont::result::Result<FontInfo> {
+        let mut info = FontInfo::new(&FIGfont::read_from(bytes)?);
+        if let Some(old_layout) = header_old_layout(bytes) {
+            info.old_layout = old_layout;
+        }
+        Ok(info)
+    }
+
+    /// Load the font at `path` and summarize it, filling name and directory.
+    pub fn load_from<P: AsRef<Path>>(path: P) -> figfont::result::Result<FontInfo> {
+        let path = path.as_ref();
+        let bytes = fs::read(path)?;
+        let mut info = if bytes.starts_with(SIGNATURE) {
+            FontInfo::read_from(&bytes)?
+        } else {
+            // zipped fonts keep the implied old layout
+            FontInfo::new(&FIGfont::load_from(path)?)
+        };
+
+        info.name = path
+            .file_stem()
+            .map(|name| name.to_string_lossy().into_owned());
+        info.directory = path.parent().map(Path::to_path_buf);
+
+        Ok(info)
+    }
+}
+
+impl Display for FontInfo {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        if let Some(ref name) = self.name {
+            writeln!(fmt, "name: {}", name)?;
+        }
+        if let Some(ref directory) = self.directory {
+            writeln!(fmt, "directory: {}", directory.display())?;
+        }
+        writeln!(fmt, "format: {}", self.format)?;
+        writeln!(fmt, "hardblank: {}", self.hardblank)?;
+        writeln!(fmt, "height: {}", self.height)?;
+        writeln!(fmt, "baseline: {}", self.baseline)?;
+        writeln!(fmt, "max length: {}", self.max_length)?;
+        writeln!(fmt, "old layout: {}", self.old_layout)?;
+        writeln!(fmt, "full layout: {}", self.full_layout)?;
+        writeln!(fmt, "horizontal layout: {}", self.horizontal_layout)?;
+        writeln!(fmt, "vertical layout: {}", self.vertical_layout)?;
+        writeln!(
+            fmt,
+            "print direction: {}",
+            if self.print_direction == 0 {
+                "left-to-right"
+            } else {
+                "right-to-left"
+            }
+        )?;
+        writeln!(fmt, "comment lines: {}", self.comment_lines)?;
+        match self.codetag_count {
+            Some(count) => write!(fmt, "code-tagged characters: {}", count),
+            None => write!(fmt, "code-tagged characters: unknown"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FontInfo;
+    use figfont::FIGfont;
+    use std::path::Path;
+
+    #[test]
+    fn standard() {
+        let info = FontInfo::new(&FIGfont::standard().unwrap());
+
+        assert_eq!(info.format, "flf2a");
+        assert_eq!(info.hardblank, "$");
+        assert_eq!(info.height, 6);
+        assert_eq!(info.baseline, 5);
+        assert_eq!(info.max_length, 16);
+        assert_eq!(info.old_layout, 15);
+        assert_eq!(info.full_layout, 24463);
+        assert_eq!(info.comment_lines, 11);
+        assert_eq!(info.codetag_count, Some(229));
+        assert_eq!(
+            info.horizontal_layout,
+            "smushing: equal, lowline, hierarchy, pair"
+        );
+    }
+
+    #[test]
+    fn load_from() {
+        let info = FontInfo::load_from("fonts/plain/term.flf").unwrap();
+
+        assert_eq!(info.name.as_deref(), Some("term"));
+        assert_eq!(info.directory.as_deref(), Some(Path::new("fonts/plain")));
+        assert_eq!(info.hardblank, "\u{7f}");
+        assert_eq!(info.old_layout, -1);
+        assert_eq!(info.horizontal_layout, "full width");
+        assert!(info
+            .to_string()
+            .starts_with("name: term\ndirectory: fonts/plain\n"));
+    }
+
+    #[test]
+    fn read_from() {
+        // the old layout contradicts the full layout
+        let font = include_str!("../fonts/plain/standard.flf").replacen(
+            "flf2a$ 6 5 16 15 ",
+            "flf2a$ 6 5 16 -1 ",
+            1,
+        );
+        let info = FontInfo::read_from(font.as_bytes()).unwrap();
+
+        assert_eq!(info.old_layout, -1);
+        assert_eq!(info.full_layout, 24463);
+    }
+
+    #[test]
+    fn read_from_wide_hardblank() {
+        let font = include_str!("../fonts/plain/standard.flf").replacen(
+            "flf2a$ 6 5 16 15 ",
+            "flf2a\u{e9}  6 5 16 -1 ",
+            1,
+        );
+        let info = FontInfo::read_from(font.as_bytes()).unwrap();
+
+        assert_eq!(info.old_layout, -1);
+    }
+}
@@ -1,10 +1,58 @@
+mod animation;
+mod bitmap;
+mod builder;
+mod chain;
+mod charmap;
+mod compose;
+mod decode;
+mod detect;
+mod effect;
 mod figure;
+mod font;
+#[cfg(feature = "bundled-fonts")]
+pub mod fonts;
+mod frame;
+mod grid;
+mod info;
 mod line;
+mod lint;
+mod output;
+#[cfg(feature = "truetype")]
+mod raster;
+mod render;
+mod specimen;
+mod split;
+mod translit;
 mod utils;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+mod writer;
 
+pub use crate::animation::{play, Marquee, Scroll, Typewriter};
+pub use crate::bitmap::{BitmapError, BitmapFont, Pixels};
+pub use crate::builder::{BuildError, FontBuilder};
+pub use crate::chain::FontChain;
+pub use crate::charmap::CharMap;
+pub use crate::compose::{side_by_side, stack, HAlign, Spacing, VAlign};
+pub use crate::decode::{Decoder, Recognition};
+pub use crate::detect::{detect_font, FontMatch};
+pub use crate::effect::{Color, Effect, Fill, Outline, Shadow};
 pub use crate::figure::FIGure;
+pub use crate::font::FIGfontExt;
+pub use crate::frame::{BorderStyle, Frame};
+pub use crate::grid::Grid;
+pub use crate::info::{describe_horizontal_layout, describe_vertical_layout, FontInfo};
 pub use crate::line::FIGline;
+pub use crate::lint::{lint, lint_file, Problem, Severity};
+pub use crate::output::{HardBlank, Tabs, Trailing, Whitespace};
+#[cfg(feature = "truetype")]
+pub use crate::raster::{RasterError, Rasterizer, Shading, RAMP};
+pub use crate::render::{RenderOptions, Rendered, Span};
+pub use crate::specimen::{Sample, Specimen};
+pub use crate::split::{classify, CharClass, WhitespaceSplitter, WordSplitter};
+pub use crate::translit::{BasicTransliterator, Transliterator};
 
+pub use crate::writer::{FontWriter, Glyph, WriteError};
 pub use figfont::*;
 
 mod prelude {
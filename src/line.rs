@@ -1,15 +1,29 @@
 use std::{
     borrow::{Borrow, Cow},
     fmt::{Display, Formatter},
+    ops::Range,
 };
 
 use figfont::{header::Layout, subcharacter::SubCharacter, FIGfont, PrintDirection};
 
+use crate::{
+    chain::FontChain,
+    output::{self, HardBlank},
+};
+
+const TAB_CODE: i32 = 9;
+
 #[derive(Clone)]
 pub struct FIGline<'a> {
-    font: &'a FIGfont,
+    chain: FontChain<'a>,
     chars: Vec<i32>,
+    spans: Vec<Range<usize>>,
     lines: Vec<Vec<SubCharacter>>,
+    hardblank: HardBlank,
+    layout: Option<Layout>,
+    /// The line was padded to a tab stop, so the next glyph is appended
+    /// like the first one instead of kerning or smushing into the padding.
+    at_stop: bool,
 }
 
 #[inline]
@@ -103,7 +117,7 @@ fn _apply_kerning(
 }
 
 #[inline]
-fn apply_kerning(
+pub(crate) fn apply_kerning(
     c1: &mut Vec<Vec<SubCharacter>>,
     c2: &mut Vec<Vec<SubCharacter>>,
     direction: PrintDirection,
@@ -343,14 +357,19 @@ fn get_smush_char(
     }
 }
 
-fn apply_smushing(
+pub(crate) fn apply_smushing(
     ch1: &mut Vec<Vec<SubCharacter>>,
     mut ch2: Vec<Vec<SubCharacter>>,
     direction: PrintDirection,
     layout: Layout,
 ) {
     let mut smush_chars: Vec<Option<SubCharacter>> = Vec::with_capacity(ch1.len());
-    if needs_smushing(layout) {
+    let touching = ch1
+        .iter()
+        .zip(ch2.iter())
+        .all(|(c1, c2)| !c1.is_empty() && !c2.is_empty());
+
+    if needs_smushing(layout) && touching {
         for i in 0..ch1.len() {
             let (c1, c2) = match direction {
                 PrintDirection::LeftToRight => (&ch1[i], &ch2[i]),
@@ -423,57 +442,151 @@ fn apply_smushing(
     }
 }
 
+/// Append `glyph` to the rows of a line, kerning or smushing it according to
+/// `layout`. The `first` glyph of a line is only trimmed.
+pub(crate) fn append_glyph(
+    lines: &mut Vec<Vec<SubCharacter>>,
+    first: bool,
+    mut glyph: Vec<Vec<SubCharacter>>,
+    direction: PrintDirection,
+    layout: Layout,
+) {
+    if first {
+        if needs_kerning(layout) {
+            match direction {
+                PrintDirection::LeftToRight => ltrim(&mut glyph),
+                PrintDirection::RightToLeft => rtrim(&mut glyph),
+            }
+        }
+
+        for (line, row) in lines.iter_mut().zip(glyph) {
+            match direction {
+                PrintDirection::LeftToRight => line.extend(row),
+                PrintDirection::RightToLeft => {
+                    line.splice(0..0, row);
+                }
+            }
+        }
+    } else {
+        if needs_kerning(layout) {
+            apply_kerning(lines, &mut glyph, direction);
+        }
+        apply_smushing(lines, glyph, direction, layout);
+    }
+}
+
 impl<'a> FIGline<'a> {
     pub fn new<'b>(font: &'b FIGfont) -> FIGline<'b> {
-        let mut lines: Vec<Vec<SubCharacter>> = Vec::with_capacity(font.header().height());
-        for _ in 0..font.header().height() {
+        FIGline::with_chain(FontChain::new(font))
+    }
+
+    pub fn with_chain<'b>(chain: FontChain<'b>) -> FIGline<'b> {
+        let height = chain.primary().header().height();
+        let mut lines: Vec<Vec<SubCharacter>> = Vec::with_capacity(height);
+        for _ in 0..height {
             lines.push(Vec::new());
         }
 
         FIGline {
-            font,
+            chain,
             chars: Vec::new(),
+            spans: Vec::new(),
             lines,
+            hardblank: HardBlank::default(),
+            layout: None,
+            at_stop: false,
         }
     }
 
-    pub fn add_char(&mut self, ch: i32) {
-        let is_empty = self.chars.is_empty();
-        self.chars.push(ch);
-        if is_empty {
-            let ch = self.font.get(ch);
-            let mut lines = ch.lines().into_owned();
-
-            if needs_kerning(self.font.header().layout()) {
-                match self.font.header().print_direction() {
-                    figfont::PrintDirection::LeftToRight => ltrim(&mut lines),
-                    figfont::PrintDirection::RightToLeft => rtrim(&mut lines),
+    pub fn font(&self) -> &'a FIGfont {
+        self.chain.primary()
+    }
+
+    pub fn chain(&self) -> &FontChain<'a> {
+        &self.chain
+    }
+
+    pub fn hardblank(&self) -> HardBlank {
+        self.hardblank
+    }
+
+    pub fn set_hardblank(&mut self, hardblank: HardBlank) {
+        self.hardblank = hardblank;
+    }
+
+    /// Get the layout glyphs are joined with, the font's unless set.
+    pub fn layout(&self) -> Layout {
+        self.layout.unwrap_or_else(|| self.font().header().layout())
+    }
+
+    pub fn set_layout(&mut self, layout: Layout) {
+        self.layout = Some(layout);
+    }
+
+    fn direction(&self) -> PrintDirection {
+        self.font().header().print_direction()
+    }
+
+    /// Record the spans of characters added to the line, now `width` columns
+    /// wide: added on the right they span the new columns, on the left they
+    /// push the previous characters right.
+    fn push_spans(
+        &mut self,
+        old: usize,
+        spans: &[Range<usize>],
+        width: usize,
+        side: PrintDirection,
+    ) {
+        match side {
+            PrintDirection::LeftToRight => {
+                let end = spans.iter().map(|span| span.end).max().unwrap_or(0);
+                let shift = width.saturating_sub(end);
+                for span in spans {
+                    self.spans
+                        .push(std::cmp::max(span.start + shift, old)..span.end + shift);
                 }
             }
-
-            for (i, line) in lines.iter().enumerate() {
-                for sch in line {
-                    self.lines[i].push(sch.clone());
+            PrintDirection::RightToLeft => {
+                let added = width.saturating_sub(old);
+                for span in self.spans.iter_mut() {
+                    *span = span.start + added..span.end + added;
+                }
+                for span in spans {
+                    self.spans.push(
+                        span.start..std::cmp::max(std::cmp::min(span.end, added), span.start),
+                    );
                 }
             }
-        } else {
-            let mut ch = self.font.get(ch).lines().into_owned();
-            apply_kerning(
-                &mut self.lines,
-                &mut ch,
-                self.font.header().print_direction(),
-            );
-            apply_smushing(
-                &mut self.lines,
-                ch,
-                self.font.header().print_direction(),
-                self.font.header().layout(),
-            );
         }
     }
 
+    pub fn add_char(&mut self, ch: i32) {
+        let old = self.width();
+        let first = self.chars.is_empty() || self.at_stop;
+        let (direction, layout) = (self.direction(), self.layout());
+        self.at_stop = false;
+        self.chars.push(ch);
+        append_glyph(
+            &mut self.lines,
+            first,
+            self.chain.glyph(ch).into_owned(),
+            direction,
+            layout,
+        );
+
+        let width = self.width();
+        let span = 0..width;
+        self.push_spans(old, std::slice::from_ref(&span), width, direction);
+    }
+
     pub fn add_line(&mut self, line: &FIGline) {
-        if self.is_empty() || !needs_kerning(self.font.header().layout()) {
+        let old = self.width();
+        let (direction, layout) = (self.direction(), self.layout());
+        let side = if self.at_stop {
+            self.chars.extend(line.chars.iter().copied());
+            append_glyph(&mut self.lines, true, line.lines.clone(), direction, layout);
+            direction
+        } else if self.is_empty() || !needs_kerning(layout) {
             for c in line.chars.iter() {
                 self.chars.push(*c);
             }
@@ -483,23 +596,54 @@ impl<'a> FIGline<'a> {
                     self.lines[i].push(sch.clone());
                 }
             }
+            PrintDirection::LeftToRight
         } else {
             for c in line.chars.iter() {
                 self.chars.push(*c);
             }
             let mut ch = line.lines.clone();
-            apply_kerning(
-                &mut self.lines,
-                &mut ch,
-                self.font.header().print_direction(),
-            );
-            apply_smushing(
-                &mut self.lines,
-                ch,
-                self.font.header().print_direction(),
-                self.font.header().layout(),
-            );
+            apply_kerning(&mut self.lines, &mut ch, direction);
+            apply_smushing(&mut self.lines, ch, direction, layout);
+            direction
+        };
+
+        let width = self.width();
+        self.push_spans(old, &line.spans, width, side);
+    }
+
+    /// Pad every row with spaces up to `width` columns, on the side the
+    /// next character will be added, which then starts at the padding.
+    pub fn pad(&mut self, width: usize) {
+        let direction = self.direction();
+        let old = self.width();
+
+        let blank = SubCharacter::Symbol(" ".to_string());
+        for line in self.lines.iter_mut() {
+            let fill = width.saturating_sub(line.iter().map(SubCharacter::width).sum());
+            let cells = vec![blank.clone(); fill];
+            match direction {
+                PrintDirection::LeftToRight => line.extend(cells),
+                PrintDirection::RightToLeft => {
+                    line.splice(0..0, cells);
+                }
+            }
         }
+        self.chars.push(TAB_CODE);
+        self.at_stop = true;
+
+        let width = self.width();
+        let span = 0..width;
+        self.push_spans(old, std::slice::from_ref(&span), width, direction);
+    }
+
+    /// Get the code of every character in the line with the columns it
+    /// spans, the ones it added to the line.
+    pub fn spans(&self) -> Vec<(i32, Range<usize>)> {
+        self.chars
+            .iter()
+            .copied()
+            .zip(self.spans.iter().cloned())
+            .collect()
     }
 
     pub fn width(&self) -> usize {
@@ -511,7 +655,7 @@ impl<'a> FIGline<'a> {
     }
 
     pub fn height(&self) -> usize {
-        self.font.header().height()
+        self.font().header().height()
     }
 
     pub fn is_empty(&self) -> bool {
@@ -525,13 +669,15 @@ impl<'a> FIGline<'a> {
 
 impl<'a> Display for FIGline<'a> {
     fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        let blank = self.hardblank.replacement(self.font());
+
         for (i, line) in self.lines.iter().enumerate() {
             if i != 0 {
                 write!(fmt, "\n")?;
             }
 
             for ch in line {
-                write!(fmt, "{}", ch)?;
+                write!(fmt, "{}", output::render(ch, &blank))?;
             }
         }
 
@@ -541,9 +687,10 @@ impl<'a> Display for FIGline<'a> {
 
 #[cfg(test)]
 mod tests {
-    use super::FIGline;
+    use super::{apply_smushing, FIGline};
+    use crate::HardBlank;
     use encoding::{all::ISO_8859_1, Encoding};
-    use figfont::FIGfont;
+    use figfont::{header::Layout, subcharacter::SubCharacter, FIGfont, PrintDirection};
 
     #[test]
     fn line_test() {
@@ -558,4 +705,64 @@ mod tests {
 
         println!("{}", line);
     }
+
+    #[test]
+    fn hardblank_test() {
+        let font = FIGfont::standard().unwrap();
+        let mut line = FIGline::new(&font);
+        line.add_char(' ' as i32);
+
+        assert_eq!(line.to_string(), " \n \n \n \n \n ");
+
+        line.set_hardblank(HardBlank::Marker);
+        assert_eq!(line.to_string(), "$\n$\n$\n$\n$\n$");
+
+        line.set_hardblank(HardBlank::NonBreakingSpace);
+        assert_eq!(line.to_string().lines().next(), Some("\u{a0}"));
+    }
+
+    #[test]
+    fn smushing_test() {
+        let sym = |s: &str| SubCharacter::Symbol(s.to_string());
+        let smush = |mut lines: Vec<Vec<SubCharacter>>, glyph| {
+            apply_smushing(
+                &mut lines,
+                glyph,
+                PrintDirection::LeftToRight,
+                Layout::HORIZONTAL_SMUSH,
+            );
+            lines
+        };
+
+        // touching rows are smushed
+        assert_eq!(
+            smush(
+                vec![vec![sym("/")], vec![sym("|")]],
+                vec![vec![sym("|")], vec![sym("\\")]]
+            ),
+            vec![vec![sym("|")], vec![sym("\\")]]
+        );
+        // an empty row touches nothing, so the glyph is only appended
+        assert_eq!(
+            smush(
+                vec![vec![], vec![sym("|")]],
+                vec![vec![sym("|")], vec![sym("|")]]
+            ),
+            vec![vec![sym("|")], vec![sym("|"), sym("|")]]
+        );
+        assert_eq!(
+            smush(
+                vec![vec![sym("|")], vec![sym("|")]],
+                vec![vec![sym("|")], vec![]]
+            ),
+            vec![vec![sym("|"), sym("|")], vec![sym("|")]]
+        );
+
+        // kerning empties the blank top row of mini's "4" before "_"
+        let font = FIGfont::load_from("fonts/plain/mini.flf").unwrap();
+        let mut line = FIGline::new(&font);
+        line.add_char('4' as i32);
+        line.add_char('_' as i32);
+        assert_eq!(line.to_string(), "     \n|_|_ \n  |  \n  __ ");
+    }
 }
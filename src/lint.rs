@@ -0,0 +1,493 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{Display, Formatter},
+    fs, io,
+    path::Path,
+};
+
+use figfont::header::Layout;
+
+use crate::{info, writer::REQUIRED_CODES};
+
+/// Sub-characters the smushing rules give a meaning to.
+const RULE_CHARS: &[u8] = b"_|/\\[]{}()<>";
+
+/// How bad a problem is.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The font loads but may render oddly.
+    Warning,
+    /// The font doesn't load, or loads wrong.
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            Severity::Warning => write!(fmt, "warning"),
+            Severity::Error => write!(fmt, "error"),
+        }
+    }
+}
+
+/// A problem found in a font file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    /// The line of the file, starting at 1.
+    pub line: usize,
+    pub severity: Severity,
+    /// The code of the character the problem is in, if any.
+    pub code: Option<i32>,
+    pub message: String,
+}
+
+impl Display for Problem {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(fmt, "{}: {}: ", self.line, self.severity)?;
+        if let Some(code) = self.code {
+            write!(fmt, "character {}: ", code)?;
+        }
+        write!(fmt, "{}", self.message)
+    }
+}
+
+struct Linter<'a> {
+    lines: Vec<&'a [u8]>,
+    problems: Vec<Problem>,
+}
+
+impl<'a> Linter<'a> {
+    fn report<S: Into<String>>(
+        &mut self,
+        line: usize,
+        severity: Severity,
+        code: Option<i32>,
+        message: S,
+    ) {
+        self.problems.push(Problem {
+            line: line + 1,
+            severity,
+            code,
+            message: message.into(),
+        });
+    }
+
+    fn header(&mut self) -> Option<Header> {
+        let line = self.lines.first().copied().unwrap_or_default();
+        if !line.starts_with(b"flf2") {
+            self.report(0, Severity::Error, None, "missing the flf2a signature");
+            return None;
+        }
+
+        let args: Vec<&[u8]> = line[5.min(line.len())..].split(|&b| b == b' ').collect();
+        let hardblank = args[0];
+        if hardblank.len() != 1 || b" \r\n".contains(&hardblank[0]) {
+            self.report(0, Severity::Error, None, "invalid hard blank");
+            return None;
+        }
+        let args: Vec<&[u8]> = args[1..]
+            .iter()
+            .filter(|arg| !arg.is_empty())
+            .copied()
+            .collect();
+        if args.len() < 5 {
+            self.report(0, Severity::Error, None, "missing header parameters");
+            return None;
+        }
+        if args.len() > 8 {
+            self.report(0, Severity::Error, None, "too many header parameters");
+        }
+
+        let names = [
+            "height",
+            "baseline",
+            "max length",
+            "old layout",
+            "comment lines",
+            "print direction",
+            "full layout",
+            "code-tagged character count",
+        ];
+        let mut values = Vec::new();
+        for (arg, name) in args.iter().zip(names.iter()) {
+            match String::from_utf8_lossy(arg).parse::<i64>() {
+                Ok(value) => values.push(Some(value)),
+                Err(_) => {
+                    self.report(0, Severity::Error, None, format!("invalid {}", name));
+                    values.push(None);
+                }
+            }
+        }
+        let (height, baseline, max_length, old_layout, comment_lines) =
+            match (values[0], values[1], values[2], values[3], values[4]) {
+                (Some(a), Some(b), Some(c), Some(d), Some(e)) => (a, b, c, d, e),
+                _ => return None,
+            };
+
+        if height < 1 {
+            self.report(0, Severity::Error, None, "the height must be at least 1");
+            return None;
+        }
+        if baseline < 1 || baseline > height {
+            self.report(
+                0,
+                Severity::Warning,
+                None,
+                format!(
+                    "the baseline {} isn't within the height {}",
+                    baseline, height
+                ),
+            );
+        }
+        if !(-1..=63).contains(&old_layout) {
+            self.report(
+                0,
+                Severity::Error,
+                None,
+                format!("the old layout {} isn't within -1 and 63", old_layout),
+            );
+        }
+        match values.get(5).copied().flatten() {
+            Some(0) | Some(1) | None => (),
+            Some(direction) => self.report(
+                0,
+                Severity::Error,
+                None,
+                format!("the print direction {} isn't 0 or 1", direction),
+            ),
+        }
+        if let Some(full_layout) = values.get(6).copied().flatten() {
+            if !(0..32768).contains(&full_layout) {
+                self.report(
+                    0,
+                    Severity::Error,
+                    None,
+                    format!("the full layout {} isn't within 0 and 32767", full_layout),
+                );
+            } else {
+                let layout = Layout::from_bits_truncate(full_layout as u32);
+                let implied = info::old_layout(layout) as i64;
+                if implied != old_layout {
+                    self.report(
+                        0,
+                        Severity::Warning,
+                        None,
+                        format!(
+                            "the full layout {} means the old layout {}, not {}",
+                            full_layout, implied, old_layout
+                        ),
+                    );
+                }
+            }
+        }
+        let codetag_count = values.get(7).copied().flatten();
+
+        Some(Header {
+            hardblank: hardblank[0],
+            height: height as usize,
+            max_length: max_length.max(0) as usize,
+            comment_lines: comment_lines.max(0) as usize,
+            codetag_count,
+        })
+    }
+
+    /// Check the character at `start`, returning the line after it.
+    fn character(&mut self, header: &Header, code: i32, start: usize) -> Option<usize> {
+        if start >= self.lines.len() {
+            self.report(
+                start,
+                Severity::Error,
+                Some(code),
+                "missing, the file ends before it",
+            );
+            return None;
+        }
+
+        // the endmark most rows end with, the last row's on ties
+        let mut counts = BTreeMap::new();
+        for line in self.lines.iter().skip(start).take(header.height) {
+            let trimmed = line.iter().rposition(|b| !b.is_ascii_whitespace());
+            if let Some(i) = trimmed {
+                *counts.entry(line[i]).or_insert(0) += 1;
+            }
+        }
+        let last = self
+            .lines
+            .get(start + header.height - 1)
+            .and_then(|line| line.iter().rev().find(|b| !b.is_ascii_whitespace()))
+            .copied();
+        let endmark = counts
+            .iter()
+            .max_by_key(|&(&mark, &count)| (count, Some(mark) == last))
+            .map(|(&mark, _)| mark);
+        let endmark = match endmark {
+            Some(endmark) => endmark,
+            None => {
+                self.report(start, Severity::Error, Some(code), "missing endmark");
+                b'@'
+            }
+        };
+        if endmark == header.hardblank {
+            self.report(
+                start,
+                Severity::Error,
+                Some(code),
+                "the endmark is the hard blank",
+            );
+        }
+
+        let mut widths = BTreeSet::new();
+        for i in start..start + header.height {
+            let line = match self.lines.get(i) {
+                Some(line) => *line,
+                None => {
+                    self.report(
+                        i,
+                        Severity::Error,
+                        Some(code),
+                        format!(
+                            "has {} rows, the font is {} rows tall",
+                            i - start,
+                            header.height
+                        ),
+                    );
+                    return None;
+                }
+            };
+
+            let trimmed = line.len()
+                - line
+                    .iter()
+                    .rev()
+                    .take_while(|b| b.is_ascii_whitespace())
+                    .count();
+            if line.last() != Some(&endmark) {
+                if trimmed > 0 && line[trimmed - 1] == endmark {
+                    self.report(
+                        i,
+                        Severity::Error,
+                        Some(code),
+                        "whitespace after the endmark",
+                    );
+                } else {
+                    self.report(i, Severity::Error, Some(code), "missing endmark");
+                }
+            }
+
+            let marks = line[..trimmed]
+                .iter()
+                .rev()
+                .take_while(|&&b| b == endmark)
+                .count();
+            // the max length counts a single endmark, many fonts double the
+            // last one anyway
+            if trimmed - marks + 1 > header.max_length {
+                self.report(
+                    i,
+                    Severity::Warning,
+                    Some(code),
+                    format!(
+                        "the row is {} long with its endmark, the max length is {}",
+                        trimmed - marks + 1,
+                        header.max_length
+                    ),
+                );
+            }
+            widths.insert(trimmed - marks);
+        }
+
+        if widths.len() > 1 {
+            self.report(
+                start,
+                Severity::Warning,
+                Some(code),
+                "the rows have different widths",
+            );
+        }
+
+        Some(start + header.height)
+    }
+
+    fn lint(&mut self) {
+        let header = match self.header() {
+            Some(header) => header,
+            None => return,
+        };
+
+        if let Some(&hardblank) = RULE_CHARS.iter().find(|&&b| b == header.hardblank) {
+            self.report(
+                0,
+                Severity::Warning,
+                None,
+                format!(
+                    "the hard blank {:?} is a sub-character of the smushing rules",
+                    hardblank as char
+                ),
+            );
+        }
+
+        let mut next = 1 + header.comment_lines;
+        if next > self.lines.len() {
+            self.report(
+                self.lines.len(),
+                Severity::Error,
+                None,
+                "the file ends in the comment lines",
+            );
+            return;
+        }
+
+        for &code in REQUIRED_CODES.iter() {
+            next = match self.character(&header, code, next) {
+                Some(next) => next,
+                None => return,
+            };
+        }
+
+        // blank lines may end the file
+        let end = self
+            .lines
+            .iter()
+            .rposition(|line| !line.iter().all(u8::is_ascii_whitespace))
+            .map_or(0, |i| i + 1);
+        let mut codes = BTreeSet::new();
+        let mut found = 0;
+        while next < end {
+            let tag = String::from_utf8_lossy(self.lines[next]);
+            let code = match parse_code(tag.split(' ').next().unwrap_or_default()) {
+                Some(code) => code,
+                None => {
+                    self.report(next, Severity::Error, None, "invalid code tag");
+                    return;
+                }
+            };
+            if code == -1 {
+                self.report(next, Severity::Error, Some(code), "the code -1 is reserved");
+            } else if !codes.insert(code) {
+                self.report(
+                    next,
+                    Severity::Warning,
+                    Some(code),
+                    "defined again, replacing the first one",
+                );
+            }
+
+            found += 1;
+            next = match self.character(&header, code, next + 1) {
+                Some(next) => next,
+                None => return,
+            };
+        }
+
+        match header.codetag_count {
+            Some(count) if count != found => self.report(
+                0,
+                Severity::Error,
+                None,
+                format!(
+                    "the header counts {} code-tagged characters, the file has {}",
+                    count, found
+                ),
+            ),
+            _ => (),
+        }
+    }
+}
+
+struct Header {
+    hardblank: u8,
+    height: usize,
+    max_length: usize,
+    comment_lines: usize,
+    codetag_count: Option<i64>,
+}
+
+/// Parse a code tag like figlet: decimal, hexadecimal after `0x`, or octal
+/// after `0`.
+pub(crate) fn parse_code(tag: &str) -> Option<i32> {
+    let (sign, tag) = match tag.strip_prefix('-') {
+        Some(tag) => (-1, tag),
+        None => (1, tag),
+    };
+
+    let code = if let Some(hex) = tag.strip_prefix("0x").or_else(|| tag.strip_prefix("0X")) {
+        i32::from_str_radix(hex, 16)
+    } else if tag.len() > 1 && tag.starts_with('0') {
+        i32::from_str_radix(&tag[1..], 8)
+    } else {
+        tag.parse()
+    };
+
+    code.ok().map(|code| code * sign)
+}
+
+/// Check the `flf2a` font `data` for problems, reading as much of it as
+/// possible.
+pub fn lint(data: &[u8]) -> Vec<Problem> {
+    let mut data = data;
+    if data.ends_with(b"\n") {
+        data = &data[..data.len() - 1];
+    }
+
+    let mut linter = Linter {
+        lines: data
+            .split(|&b| b == b'\n')
+            .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+            .collect(),
+        problems: Vec::new(),
+    };
+    linter.lint();
+
+    let mut problems = linter.problems;
+    problems.sort_by_key(|problem| problem.line);
+    problems
+}
+
+/// Check the font file at `path` for problems.
+pub fn lint_file<P: AsRef<Path>>(path: P) -> io::Result<Vec<Problem>> {
+    Ok(lint(&fs::read(path)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{lint, lint_file, Severity};
+
+    #[test]
+    fn bundled() {
+        for entry in std::fs::read_dir("fonts/plain").unwrap() {
+            let path = entry.unwrap().path();
+            let errors: Vec<_> = lint_file(&path)
+                .unwrap()
+                .into_iter()
+                .filter(|problem| problem.severity == Severity::Error)
+                .collect();
+
+            assert!(errors.is_empty(), "{}: {:?}", path.display(), errors);
+        }
+    }
+
+    #[test]
+    fn problems() {
+        let font = std::fs::read_to_string("fonts/plain/standard.flf").unwrap();
+        let mut lines: Vec<&str> = font.lines().collect();
+        // "!" loses an endmark, '"' gets too wide, "#" loses the endmark of
+        // its first row and doubles another one
+        lines[19] = " | |";
+        lines[24] = "  _ _ ______________@";
+        lines[30] = "    _  _   ";
+        lines[31] = "  _| || |_ @@";
+
+        let problems = lint(lines.join("\n").as_bytes());
+        let rendered: Vec<String> = problems.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            rendered,
+            [
+                "20: error: character 33: missing endmark",
+                "25: warning: character 34: the row is 21 long with its endmark, the max length is 16",
+                "25: warning: character 34: the rows have different widths",
+                "31: error: character 35: missing endmark",
+                "31: warning: character 35: the rows have different widths",
+            ]
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use std::borrow::Cow;
+
+use encoding::{all::ISO_8859_1, DecoderTrap, Encoding};
+use figfont::{subcharacter::SubCharacter, FIGfont};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// How hard blanks are rendered.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum HardBlank {
+    /// Render hard blanks as regular spaces.
+    #[default]
+    Space,
+    /// Render hard blanks as the font's own hard blank character.
+    Marker,
+    /// Render hard blanks as non-breaking spaces (U+00A0).
+    NonBreakingSpace,
+}
+
+/// What to do with the trailing whitespace of every row.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum Trailing {
+    /// Leave rows as the glyphs left them.
+    #[default]
+    Preserve,
+    /// Remove trailing whitespace from every row.
+    Trim,
+    /// Pad every row with spaces up to the FIGure's width.
+    Pad,
+}
+
+/// How whitespace between words is laid out.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum Whitespace {
+    /// Render every space, even at the start or end of wrapped rows.
+    #[default]
+    Preserve,
+    /// Drop the spaces where a row wraps, like figlet.
+    Trim,
+    /// Collapse runs of spaces to the first one and drop the spaces where a
+    /// row wraps.
+    Collapse,
+}
+
+/// How tabs are expanded.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum Tabs {
+    /// Expand every tab to this many space glyphs.
+    Spaces(usize),
+    /// Align the text after a tab to the next multiple of this many output
+    /// columns.
+    Stops(usize),
+}
+
+impl Default for Tabs {
+    /// Like figlet, a tab is a single space.
+    fn default() -> Self {
+        Tabs::Spaces(1)
+    }
+}
+
+impl HardBlank {
+    /// Get the string a hard blank is rendered to with `font`.
+    pub fn replacement(self, font: &FIGfont) -> Cow<'static, str> {
+        match self {
+            HardBlank::Space => Cow::Borrowed(" "),
+            HardBlank::NonBreakingSpace => Cow::Borrowed("\u{a0}"),
+            HardBlank::Marker => {
+                match ISO_8859_1.decode(font.header().hard_blank_char(), DecoderTrap::Replace) {
+                    Ok(marker) => Cow::Owned(marker),
+                    Err(_) => Cow::Borrowed(" "),
+                }
+            }
+        }
+    }
+}
+
+#[inline]
+pub(crate) fn is_blank_row(row: &str) -> bool {
+    row.trim().is_empty()
+}
+
+#[inline]
+pub(crate) fn render<'a>(sch: &'a SubCharacter, blank: &'a str) -> &'a str {
+    match sch {
+        SubCharacter::Blank => blank,
+        SubCharacter::Symbol(sym) => sym,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HardBlank;
+    use figfont::FIGfont;
+
+    #[test]
+    fn replacement() {
+        let font = FIGfont::standard().unwrap();
+
+        assert_eq!(HardBlank::Space.replacement(&font), " ");
+        assert_eq!(HardBlank::Marker.replacement(&font), "$");
+        assert_eq!(HardBlank::NonBreakingSpace.replacement(&font), "\u{a0}");
+    }
+}
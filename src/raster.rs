@@ -0,0 +1,347 @@
+use std::{fs, io, path::Path};
+
+use ab_glyph::{point, Font, FontVec, InvalidFont, PxScale, ScaleFont};
+use figfont::{header::Layout, subcharacter::SubCharacter, FIGfont};
+use thiserror::Error;
+
+use crate::writer::{FontWriter, WriteError};
+
+/// The ramp of `Shading::Ramp` from no coverage to full coverage.
+pub const RAMP: &str = " .:-=+*#%@";
+
+// quadrant blocks by their top left, top right, bottom left and bottom right
+// bits
+const QUADRANTS: [char; 16] = [
+    ' ', '▘', '▝', '▀', '▖', '▌', '▞', '▛', '▗', '▚', '▐', '▜', '▄', '▙', '▟', '█',
+];
+
+// braille dots by their position in the cell, column first
+const BRAILLE: [[u32; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+
+#[derive(Debug, Error)]
+pub enum RasterError {
+    #[error(transparent)]
+    Font(#[from] InvalidFont),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// How the coverage of pixels is drawn with sub-characters.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shading {
+    /// The character for every pixel covered at least this much.
+    Threshold(char, f32),
+    /// A character for every pixel, from the first one for no coverage to the
+    /// last one for full coverage.
+    Ramp(String),
+    /// Quadrant blocks, two by two pixels in every sub-character.
+    Blocks,
+    /// Braille patterns, two by four pixels in every sub-character.
+    Braille,
+}
+
+impl Default for Shading {
+    fn default() -> Self {
+        Shading::Threshold('#', 0.5)
+    }
+}
+
+impl Shading {
+    /// Get the pixels in every sub-character, wide and high.
+    fn cell(&self) -> (usize, usize) {
+        match self {
+            Shading::Threshold(..) | Shading::Ramp(_) => (1, 1),
+            Shading::Blocks => (2, 2),
+            Shading::Braille => (2, 4),
+        }
+    }
+
+    /// Get the characters of the threshold or of the ramp.
+    fn chars(&self) -> Vec<char> {
+        match self {
+            Shading::Threshold(ch, _) => vec![*ch],
+            Shading::Ramp(ramp) => ramp.chars().collect(),
+            Shading::Blocks | Shading::Braille => Vec::new(),
+        }
+    }
+
+    /// Shade the `coverage` of every pixel, with the `ramp` got from `chars`.
+    fn shade(&self, coverage: &[Vec<f32>], ramp: &[char]) -> Vec<Vec<SubCharacter>> {
+        let (cell_width, cell_height) = self.cell();
+        let width = coverage.first().map_or(0, Vec::len);
+        let pixel = |y: usize, x: usize| {
+            coverage
+                .get(y)
+                .and_then(|row| row.get(x))
+                .copied()
+                .unwrap_or(0.0)
+        };
+        let set = |y: usize, x: usize| pixel(y, x) >= 0.5;
+
+        (0..coverage.len().div_ceil(cell_height))
+            .map(|row| {
+                (0..width.div_ceil(cell_width))
+                    .map(|col| {
+                        let (y, x) = (row * cell_height, col * cell_width);
+                        let ch = match self {
+                            Shading::Threshold(ch, level) if pixel(y, x) >= *level => *ch,
+                            Shading::Threshold(..) => ' ',
+                            Shading::Ramp(_) => {
+                                let last = ramp.len().saturating_sub(1);
+                                let i = (pixel(y, x).clamp(0.0, 1.0) * last as f32).round();
+                                ramp.get(i as usize).copied().unwrap_or(' ')
+                            }
+                            Shading::Blocks => {
+                                let bits = set(y, x) as usize
+                                    | (set(y, x + 1) as usize) << 1
+                                    | (set(y + 1, x) as usize) << 2
+                                    | (set(y + 1, x + 1) as usize) << 3;
+                                QUADRANTS[bits]
+                            }
+                            Shading::Braille => {
+                                let mut bits = 0;
+                                for (dx, dots) in BRAILLE.iter().enumerate() {
+                                    for (dy, dot) in dots.iter().enumerate() {
+                                        if set(y + dy, x + dx) {
+                                            bits |= dot;
+                                        }
+                                    }
+                                }
+                                match bits {
+                                    0 => ' ',
+                                    bits => char::from_u32(0x2800 + bits).unwrap_or(' '),
+                                }
+                            }
+                        };
+                        SubCharacter::from(ch)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Rasterizes the glyphs of a TrueType or OpenType font to make a FIGfont.
+pub struct Rasterizer {
+    font: FontVec,
+    size: f32,
+    shading: Shading,
+    chars: Vec<char>,
+}
+
+impl Rasterizer {
+    /// Create a rasterizer for the font `data`, 16 pixels high, converting
+    /// ASCII and Latin-1.
+    pub fn new(data: Vec<u8>) -> Result<Rasterizer, RasterError> {
+        Ok(Rasterizer {
+            font: FontVec::try_from_vec(data)?,
+            size: 16.0,
+            shading: Shading::default(),
+            chars: (' '..='~').chain('\u{a0}'..='ÿ').collect(),
+        })
+    }
+
+    pub fn load_from<P: AsRef<Path>>(path: P) -> Result<Rasterizer, RasterError> {
+        Rasterizer::new(fs::read(path)?)
+    }
+
+    /// Get the height of the font in pixels.
+    pub fn size(&self) -> f32 {
+        self.size
+    }
+
+    pub fn set_size(&mut self, size: f32) {
+        self.size = size;
+    }
+
+    pub fn shading(&self) -> &Shading {
+        &self.shading
+    }
+
+    pub fn set_shading(&mut self, shading: Shading) {
+        self.shading = shading;
+    }
+
+    /// Get the characters converted, when the font has them.
+    pub fn chars(&self) -> &[char] {
+        &self.chars
+    }
+
+    pub fn set_chars<I: IntoIterator<Item = char>>(&mut self, chars: I) {
+        self.chars = chars.into_iter().collect();
+    }
+
+    /// Get a writer for the FIGfont drawing the rasterized glyphs.
+    ///
+    /// Sub-characters being about twice as high as wide, pixels are twice as
+    /// wide as high unless the shading already draws them that way. Glyphs
+    /// are trimmed to their ink and a column of hard blanks to kern like other
+    /// FIGfonts: the font's advances and side bearings are lost, except for
+    /// blank glyphs which keep their advance. When the shading draws `$`,
+    /// the hard blank is the first character from `\u{7f}` it doesn't draw.
+    pub fn to_writer(&self) -> FontWriter {
+        let (cell_width, cell_height) = self.shading.cell();
+        let scale = PxScale {
+            x: self.size * 2.0 * cell_width as f32 / cell_height as f32,
+            y: self.size,
+        };
+        let font = self.font.as_scaled(scale);
+        let ascent = font.ascent().ceil().max(0.0) as usize;
+        let height = std::cmp::max(ascent + font.descent().abs().ceil() as usize, 1);
+
+        let mut writer = FontWriter::empty(height.div_ceil(cell_height));
+        writer.set_baseline(std::cmp::max(ascent.div_ceil(cell_height), 1));
+        writer.set_layout(Layout::HORIZONTAL_KERNING);
+        let chars = self.shading.chars();
+        if chars.contains(&'$') {
+            let hardblank = ('\u{7f}'..=char::MAX).find(|ch| !chars.contains(ch));
+            writer.set_hardblank(hardblank.unwrap_or('\u{7f}').to_string());
+        }
+
+        for &ch in self.chars.iter() {
+            let id = self.font.glyph_id(ch);
+            if id.0 == 0 && ch != '\0' {
+                continue;
+            }
+
+            let advance = font.h_advance(id).round().max(0.0) as usize;
+            let mut coverage = vec![vec![0.0; advance]; height];
+            let glyph = id.with_scale_and_position(scale, point(0.0, ascent as f32));
+            if let Some(outline) = self.font.outline_glyph(glyph) {
+                let bounds = outline.px_bounds();
+                let left = bounds.min.x.min(0.0);
+                let width = bounds.max.x.ceil() - left;
+                for row in coverage.iter_mut() {
+                    row.resize(std::cmp::max(advance, width as usize), 0.0);
+                }
+                outline.draw(|x, y, c| {
+                    let x = (bounds.min.x - left) as usize + x as usize;
+                    let y = bounds.min.y as i64 + y as i64;
+                    if y >= 0 {
+                        if let Some(pixel) =
+                            coverage.get_mut(y as usize).and_then(|row| row.get_mut(x))
+                        {
+                            *pixel = c;
+                        }
+                    }
+                });
+            }
+
+            let mut lines = self.shading.shade(&coverage, &chars);
+            fit(&mut lines, advance.div_ceil(cell_width));
+            writer.set_glyph(ch as i32, lines, None);
+        }
+
+        writer
+    }
+
+    /// Convert to a FIGfont drawing the rasterized glyphs.
+    pub fn to_font(&self) -> Result<FIGfont, WriteError> {
+        self.to_writer().to_font()
+    }
+}
+
+/// Trim glyph `lines` to their ink, keeping their shape but a single column
+/// of spacing which kerning doesn't remove. Blank glyphs become `width` hard
+/// blanks to keep their advance.
+fn fit(lines: &mut [Vec<SubCharacter>], width: usize) {
+    let ink = |x: usize| {
+        lines.iter().any(|line| match line.get(x) {
+            Some(SubCharacter::Symbol(sym)) => !sym.trim().is_empty(),
+            _ => false,
+        })
+    };
+    let len = lines.iter().map(Vec::len).max().unwrap_or(0);
+
+    match ((0..len).position(ink), (0..len).rposition(ink)) {
+        (Some(first), Some(last)) => {
+            for line in lines.iter_mut() {
+                line.resize(last + 1, SubCharacter::from(' '));
+                line.drain(..first);
+                line.push(SubCharacter::Blank);
+            }
+        }
+        _ => {
+            for line in lines.iter_mut() {
+                *line = vec![SubCharacter::Blank; std::cmp::max(width, 1)];
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Rasterizer, Shading};
+    use crate::FIGure;
+
+    #[test]
+    fn rasterize() {
+        // a test font, with the space, "I" and "L" drawn with rectangles
+        let mut rasterizer = Rasterizer::load_from("fonts/truetype/boxes.ttf").unwrap();
+        rasterizer.set_size(10.0);
+        let font = rasterizer.to_font().unwrap();
+        let mut figure = FIGure::new(&font, 80);
+        figure.add("I L").unwrap();
+
+        // 8 pixels above the baseline and 2 below, pixels twice as wide
+        assert_eq!(font.header().height(), 10);
+        assert_eq!(font.header().baseline(), 8);
+        let mut rows = vec!["####           ####         "; 6];
+        rows.extend(vec!["####           ############ "; 2]);
+        rows.extend(vec!["                            "; 2]);
+        assert_eq!(figure.rows(), rows);
+
+        rasterizer.set_shading(Shading::Blocks);
+        let font = rasterizer.to_font().unwrap();
+        let mut figure = FIGure::new(&font, 80);
+        figure.add("L").unwrap();
+        assert_eq!(
+            figure.rows(),
+            vec!["██     ", "██     ", "██     ", "██████ ", "       "]
+        );
+
+        // shading with the usual hard blank picks another one
+        for shading in [
+            Shading::Threshold('$', 0.5),
+            Shading::Ramp(" \u{7f}$".into()),
+        ] {
+            rasterizer.set_shading(shading);
+            let font = rasterizer.to_font().unwrap();
+            let mut figure = FIGure::new(&font, 80);
+            figure.add("L").unwrap();
+            assert_eq!(figure.rows()[7], "$$$$$$$$$$$$ ");
+        }
+    }
+
+    #[test]
+    fn shading() {
+        let coverage = vec![
+            vec![1.0, 0.2, 0.0, 0.6],
+            vec![0.0, 0.6, 1.0, 1.0],
+            vec![0.0, 0.0, 0.0, 0.0],
+            vec![1.0, 1.0, 0.0, 0.0],
+        ];
+        let rows = |shading: Shading| -> Vec<String> {
+            shading
+                .shade(&coverage, &shading.chars())
+                .iter()
+                .map(|line| line.iter().map(|sch| sch.to_string()).collect())
+                .collect()
+        };
+
+        assert_eq!(
+            rows(Shading::default()),
+            vec!["#  #", " ###", "    ", "##  "]
+        );
+        assert_eq!(
+            rows(Shading::Ramp(" .#".to_string())),
+            vec!["#  .", " .##", "    ", "##  "]
+        );
+        assert_eq!(
+            rows(Shading::Ramp(super::RAMP.to_string())),
+            vec!["@: +", " +@@", "    ", "@@  "]
+        );
+        assert_eq!(rows(Shading::Blocks), vec!["▚▟", "▄ "]);
+        assert_eq!(rows(Shading::Braille), vec!["⣑⠚"]);
+    }
+}
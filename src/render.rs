@@ -0,0 +1,193 @@
+use std::borrow::Cow;
+
+use figfont::{header::Layout, FIGfont};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    compose::HAlign,
+    output::{HardBlank, Tabs, Trailing, Whitespace},
+    FIGure,
+};
+
+/// Where a character is in a rendered FIGure.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Span {
+    /// The code of the glyph.
+    pub code: i32,
+    /// The first row of the glyph.
+    pub row: usize,
+    /// The rows of the glyph, without the ones stripped as blank.
+    pub height: usize,
+    /// The first column the glyph added to its row.
+    pub start: usize,
+    /// The column after the last one the glyph added to its row.
+    pub end: usize,
+}
+
+/// A rendered FIGure, detached from its font.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Rendered {
+    pub rows: Vec<String>,
+    pub width: usize,
+    pub height: usize,
+    pub baseline: usize,
+    pub spans: Vec<Span>,
+}
+
+impl Rendered {
+    pub fn new(figure: &FIGure) -> Rendered {
+        let (grid, spans) = figure.render();
+
+        Rendered {
+            rows: grid.to_rows(),
+            width: grid.width(),
+            height: grid.height(),
+            baseline: grid.baseline(),
+            spans,
+        }
+    }
+}
+
+/// The settings of a FIGure, to keep in a configuration file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct RenderOptions {
+    /// The name of the font, for the application to look up.
+    pub font: Option<String>,
+    pub width: usize,
+    /// Justification, following the print direction when unset.
+    pub justify: Option<HAlign>,
+    /// Full layout bits overriding the font's, as in a FIGfont header.
+    pub layout: Option<u32>,
+    pub hardblank: HardBlank,
+    pub trailing: Trailing,
+    pub strip_blank_rows: bool,
+    pub whitespace: Whitespace,
+    pub tabs: Tabs,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            font: None,
+            width: 80,
+            justify: None,
+            layout: None,
+            hardblank: HardBlank::default(),
+            trailing: Trailing::default(),
+            strip_blank_rows: false,
+            whitespace: Whitespace::default(),
+            tabs: Tabs::default(),
+        }
+    }
+}
+
+impl RenderOptions {
+    /// Create an empty FIGure for `font` with these settings.
+    pub fn figure<'f>(&self, font: &'f FIGfont) -> FIGure<'f> {
+        let mut figure = FIGure::new(font, self.width);
+        if let Some(justify) = self.justify {
+            figure.set_justify(justify);
+        }
+        if let Some(layout) = self.layout {
+            figure.set_layout(Layout::from_bits_truncate(layout));
+        }
+        figure.set_hardblank(self.hardblank);
+        figure.set_trailing(self.trailing);
+        figure.set_strip_blank_rows(self.strip_blank_rows);
+        figure.set_whitespace(self.whitespace);
+        figure.set_tabs(self.tabs);
+        figure
+    }
+
+    /// Render `text` in `font` with these settings.
+    pub fn render<'f>(&self, font: &'f FIGfont, text: &str) -> Result<Rendered, Cow<'f, str>> {
+        let mut figure = self.figure(font);
+        figure
+            .add(text)
+            .map_err(|err| Cow::Owned(err.into_owned()))?;
+        Ok(Rendered::new(&figure))
+    }
+
+    /// Look up the font among the bundled ones, `standard` when unset.
+    #[cfg(feature = "bundled-fonts")]
+    pub fn bundled_font(&self) -> Option<&'static FIGfont> {
+        crate::fonts::by_name(self.font.as_deref().unwrap_or("standard"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RenderOptions, Span};
+    use crate::compose::HAlign;
+    use figfont::{header::Layout, FIGfont};
+
+    #[test]
+    fn render() {
+        let font = FIGfont::standard().unwrap();
+        let options = RenderOptions {
+            width: 20,
+            justify: Some(HAlign::Center),
+            layout: Some(Layout::HORIZONTAL_KERNING.bits()),
+            strip_blank_rows: true,
+            ..RenderOptions::default()
+        };
+        let rendered = options.render(&font, "ab").unwrap();
+
+        assert_eq!(
+            rendered.rows,
+            vec![
+                "           _     ",
+                "     __ _ | |__  ",
+                "    / _` || '_ \\ ",
+                "   | (_| || |_) |",
+                "    \\__,_||_.__/ ",
+            ]
+        );
+        assert_eq!(
+            rendered.spans,
+            vec![
+                Span {
+                    code: 'a' as i32,
+                    row: 0,
+                    height: 5,
+                    start: 3,
+                    end: 10,
+                },
+                Span {
+                    code: 'b' as i32,
+                    row: 0,
+                    height: 5,
+                    start: 10,
+                    end: 17,
+                },
+            ]
+        );
+
+        let rendered = options.render(&font, "a").unwrap();
+        assert_eq!(rendered.height, 4);
+        assert_eq!(rendered.spans[0].row, 0);
+        assert_eq!(rendered.spans[0].height, 4);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let options: RenderOptions =
+            serde_json::from_str(r#"{"font": "slant", "width": 40, "justify": "right"}"#).unwrap();
+
+        assert_eq!(options.font.as_deref(), Some("slant"));
+        assert_eq!(options.justify, Some(HAlign::Right));
+        assert_eq!(options.tabs, Default::default());
+
+        let json = serde_json::to_string(&options).unwrap();
+        assert_eq!(
+            serde_json::from_str::<RenderOptions>(&json).unwrap(),
+            options
+        );
+    }
+}
@@ -0,0 +1,303 @@
+use std::{fmt::Write, fs, io, path::Path};
+
+use figfont::{subcharacter::SubCharacter, FIGfont};
+
+use crate::{
+    compose::{side_by_side, stack, HAlign, Spacing, VAlign},
+    font::FIGfontExt,
+    grid::Grid,
+    line::FIGline,
+    output::Trailing,
+    writer::{codetagged, REQUIRED_CODES},
+    FIGure,
+};
+
+/// Columns between the samples of a row.
+const GAP: usize = 2;
+/// Size of a cell in SVG output, in pixels.
+const SVG_CELL: (f64, f64) = (8.4, 16.0);
+
+/// A labelled rendering in a specimen sheet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sample {
+    pub label: String,
+    pub grid: Grid,
+}
+
+/// A sheet of labelled samples laid out in columns, like figlet's
+/// `showfigfonts`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Specimen {
+    samples: Vec<Sample>,
+    columns: usize,
+}
+
+fn label(code: i32) -> String {
+    match std::char::from_u32(code as u32) {
+        _ if code < 0 => format!("{}", code),
+        Some(ch) if !ch.is_control() && !ch.is_whitespace() => format!("U+{:04X} {}", code, ch),
+        _ => format!("U+{:04X}", code),
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut res = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => res.push_str("&amp;"),
+            '<' => res.push_str("&lt;"),
+            '>' => res.push_str("&gt;"),
+            '"' => res.push_str("&quot;"),
+            ch => res.push(ch),
+        }
+    }
+    res
+}
+
+impl Specimen {
+    pub fn new(samples: Vec<Sample>, columns: usize) -> Specimen {
+        Specimen { samples, columns }
+    }
+
+    /// Render every character of `font`: ASCII, Latin-1 and code-tagged
+    /// characters, labelled with their code.
+    pub fn charset(font: &FIGfont, columns: usize) -> Specimen {
+        let mut codes: Vec<i32> = REQUIRED_CODES
+            .iter()
+            .copied()
+            .chain((160..256).filter(|&code| font.has_glyph(code)))
+            .chain(codetagged(font))
+            .collect();
+        // negative codes last, they aren't characters
+        codes.sort_by_key(|&code| (code < 0, code.abs()));
+        codes.dedup();
+
+        let samples = codes
+            .into_iter()
+            .map(|code| {
+                let mut line = FIGline::new(font);
+                line.add_char(code);
+                let grid = Grid::new(line.lines().into_owned(), font.header().baseline(), " ");
+                Sample {
+                    label: label(code),
+                    grid,
+                }
+            })
+            .collect();
+
+        Specimen::new(samples, columns)
+    }
+
+    /// Render `text` in every font, labelled with their names.
+    pub fn fonts<'f, I, S>(fonts: I, text: &str, width: usize) -> Specimen
+    where
+        I: IntoIterator<Item = (S, &'f FIGfont)>,
+        S: Into<String>,
+    {
+        let samples = fonts
+            .into_iter()
+            .map(|(name, font)| {
+                let mut figure = FIGure::new(font, width);
+                figure.set_trailing(Trailing::Trim);
+                figure.set_strip_blank_rows(true);
+                // characters the font lacks are rendered with its fallback
+                let _ = figure.add(text);
+                Sample {
+                    label: name.into(),
+                    grid: figure.grid(),
+                }
+            })
+            .collect();
+
+        Specimen::new(samples, 1)
+    }
+
+    /// Render `text` in every font of the directory `dir`, by name. Fonts
+    /// that fail to load are listed with the error.
+    pub fn load_dir<P: AsRef<Path>>(dir: P, text: &str, width: usize) -> io::Result<Specimen> {
+        let mut paths: Vec<_> = fs::read_dir(dir)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<io::Result<_>>()?;
+        paths.retain(|path| path.extension().is_some_and(|ext| ext == "flf"));
+        paths.sort();
+
+        let mut samples = Vec::with_capacity(paths.len());
+        for path in paths {
+            let name = path
+                .file_stem()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            match FIGfont::load_from(&path) {
+                Ok(font) => samples.extend(Specimen::fonts([(name, &font)], text, width).samples),
+                Err(err) => samples.push(Sample {
+                    label: format!("{}: {}", name, err),
+                    grid: Grid::new(Vec::new(), 0, " "),
+                }),
+            }
+        }
+
+        Ok(Specimen::new(samples, 1))
+    }
+
+    pub fn samples(&self) -> &[Sample] {
+        &self.samples
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn set_columns(&mut self, columns: usize) {
+        self.columns = columns;
+    }
+
+    /// Lay out the samples, each under its label, in rows of `columns`
+    /// samples as wide as the widest one.
+    pub fn grid(&self) -> Grid {
+        let cells: Vec<Grid> = self
+            .samples
+            .iter()
+            .map(|sample| {
+                let label = vec![sample.label.chars().map(SubCharacter::from).collect()];
+                stack(
+                    &[Grid::new(label, 1, " "), sample.grid.clone()],
+                    0,
+                    HAlign::Left,
+                )
+            })
+            .collect();
+        let width = cells.iter().map(Grid::width).max().unwrap_or(0);
+
+        let rows: Vec<Grid> = cells
+            .chunks(std::cmp::max(self.columns, 1))
+            .map(|cells| {
+                let cells: Vec<Grid> = cells
+                    .iter()
+                    .cloned()
+                    .map(|mut cell| {
+                        cell.pad(width);
+                        cell
+                    })
+                    .collect();
+                side_by_side(&cells, Spacing::Gap(GAP), VAlign::Top)
+            })
+            .collect();
+
+        let mut res = stack(&rows, 1, HAlign::Left);
+        res.trim();
+        res
+    }
+
+    /// Render as plain text.
+    pub fn to_text(&self) -> String {
+        self.grid().to_string()
+    }
+
+    /// Render as an HTML fragment, a CSS grid of captioned `<pre>` blocks.
+    pub fn to_html(&self) -> String {
+        let mut res = String::new();
+        let _ = writeln!(
+            res,
+            "<div class=\"riglet-specimen\" style=\"display: grid; \
+             grid-template-columns: repeat({}, auto); gap: 1em;\">",
+            std::cmp::max(self.columns, 1)
+        );
+        for sample in self.samples.iter() {
+            let mut grid = sample.grid.clone();
+            grid.trim();
+            let rows: Vec<String> = grid.to_rows().iter().map(|row| escape(row)).collect();
+            let _ = writeln!(
+                res,
+                "<figure><figcaption>{}</figcaption><pre>{}</pre></figure>",
+                escape(&sample.label),
+                rows.join("\n")
+            );
+        }
+        res.push_str("</div>\n");
+        res
+    }
+
+    /// Render as an SVG image of the text layout, in a monospace font.
+    pub fn to_svg(&self) -> String {
+        let grid = self.grid();
+        let (cell_width, cell_height) = SVG_CELL;
+        let width = (grid.width() as f64 * cell_width).ceil();
+        let height = grid.height() as f64 * cell_height;
+
+        let mut res = String::new();
+        let _ = writeln!(
+            res,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+             font-family=\"monospace\" font-size=\"14\" xml:space=\"preserve\">",
+            width, height
+        );
+        for (i, row) in grid.to_rows().iter().enumerate() {
+            if row.is_empty() {
+                continue;
+            }
+            let _ = writeln!(
+                res,
+                "<text x=\"0\" y=\"{}\">{}</text>",
+                (i + 1) as f64 * cell_height - 4.0,
+                escape(row)
+            );
+        }
+        res.push_str("</svg>\n");
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Specimen;
+    use figfont::FIGfont;
+    use std::fs;
+
+    #[test]
+    fn charset() {
+        let font = FIGfont::load_from("fonts/plain/term.flf").unwrap();
+        let specimen = Specimen::charset(&font, 4);
+        let labels: Vec<&str> = specimen
+            .samples()
+            .iter()
+            .map(|sample| sample.label.as_str())
+            .collect();
+
+        assert_eq!(labels[..3], ["U+0020", "U+0021 !", "U+0022 \""]);
+        assert!(labels.contains(&"U+00E9 é"));
+
+        let text = specimen.to_text();
+        let rows: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            rows[..2],
+            [
+                "U+0020    U+0021 !  U+0022 \"  U+0023 #",
+                "          !         \"         #"
+            ]
+        );
+        assert!(specimen
+            .to_html()
+            .contains("<figcaption>U+0022 &quot;</figcaption><pre>&quot;</pre>"));
+        assert!(specimen.to_svg().starts_with("<svg"));
+    }
+
+    #[test]
+    fn load_dir() {
+        let specimen = Specimen::load_dir("fonts/plain", "Hi", 80).unwrap();
+        let fonts = fs::read_dir("fonts/plain")
+            .unwrap()
+            .filter(|entry| {
+                let path = entry.as_ref().unwrap().path();
+                path.extension().is_some_and(|ext| ext == "flf")
+            })
+            .count();
+
+        assert_eq!(specimen.samples().len(), fonts);
+        assert_eq!(specimen.samples()[0].label, "banner");
+        let term = specimen
+            .samples()
+            .iter()
+            .find(|sample| sample.label == "term");
+        assert_eq!(term.unwrap().grid.to_rows(), vec!["Hi"]);
+    }
+}
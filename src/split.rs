@@ -0,0 +1,118 @@
+use crate::utils::SplitWords;
+
+/// How the word splitter treats a character.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CharClass {
+    /// Part of a word.
+    Word,
+    /// A space between words, rendered as its own item.
+    Space,
+    /// An invisible break opportunity between words, not rendered.
+    Break,
+    /// Part of a word, with a break opportunity after it.
+    BreakAfter,
+}
+
+/// Default classification: Unicode whitespace separates words, except the
+/// no-break spaces which join them, and zero-width spaces are invisible
+/// break opportunities.
+pub fn classify(ch: char) -> CharClass {
+    match ch {
+        // NO-BREAK SPACE, FIGURE SPACE, NARROW NO-BREAK SPACE
+        '\u{a0}' | '\u{2007}' | '\u{202f}' => CharClass::Word,
+        // WORD JOINER, ZERO WIDTH NO-BREAK SPACE
+        '\u{2060}' | '\u{feff}' => CharClass::Word,
+        // ZERO WIDTH SPACE
+        '\u{200b}' => CharClass::Break,
+        ch if ch.is_whitespace() => CharClass::Space,
+        _ => CharClass::Word,
+    }
+}
+
+/// Splits text in the items a FIGure lays out, wrapping only between them.
+pub trait WordSplitter {
+    fn split(&self, text: &str) -> Vec<String>;
+
+    /// Check if an item returned by `split` is whitespace between words.
+    fn is_space(&self, item: &str) -> bool {
+        !item.is_empty() && item.chars().all(|ch| classify(ch) == CharClass::Space)
+    }
+}
+
+impl<F> WordSplitter for F
+where
+    F: Fn(&str) -> Vec<String>,
+{
+    fn split(&self, text: &str) -> Vec<String> {
+        self(text)
+    }
+}
+
+/// Splits words on whitespace according to a character classifier, every
+/// space becoming an item on its own.
+#[derive(Debug, Copy, Clone)]
+pub struct WhitespaceSplitter {
+    classify: fn(char) -> CharClass,
+}
+
+impl Default for WhitespaceSplitter {
+    fn default() -> Self {
+        WhitespaceSplitter::new(classify)
+    }
+}
+
+impl WhitespaceSplitter {
+    pub fn new(classify: fn(char) -> CharClass) -> WhitespaceSplitter {
+        WhitespaceSplitter { classify }
+    }
+
+    pub fn classifier(&self) -> fn(char) -> CharClass {
+        self.classify
+    }
+}
+
+impl WordSplitter for WhitespaceSplitter {
+    fn split(&self, text: &str) -> Vec<String> {
+        SplitWords::with_classifier(text, self.classify).collect()
+    }
+
+    fn is_space(&self, item: &str) -> bool {
+        !item.is_empty()
+            && item
+                .chars()
+                .all(|ch| (self.classify)(ch) == CharClass::Space)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CharClass, WhitespaceSplitter, WordSplitter};
+
+    #[test]
+    fn unicode_whitespace() {
+        let splitter = WhitespaceSplitter::default();
+
+        assert_eq!(
+            splitter.split("a\u{2003}b\u{3000}c"),
+            vec!["a", "\u{2003}", "b", "\u{3000}", "c"]
+        );
+        assert_eq!(splitter.split("10\u{a0}km"), vec!["10\u{a0}km"]);
+        assert_eq!(splitter.split("long\u{200b}word"), vec!["long", "word"]);
+        assert!(splitter.is_space("\u{3000}"));
+        assert!(!splitter.is_space("\u{a0}"));
+    }
+
+    #[test]
+    fn custom_classifier() {
+        let splitter = WhitespaceSplitter::new(|ch| match ch {
+            '/' | '\u{3000}'..='\u{9fff}' => CharClass::BreakAfter,
+            ch => super::classify(ch),
+        });
+
+        assert_eq!(
+            splitter.split("http://a/b c"),
+            vec!["http:/", "/", "a/", "b", " ", "c"]
+        );
+        assert_eq!(splitter.split("漢字"), vec!["漢", "字"]);
+    }
+}
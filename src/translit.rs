@@ -0,0 +1,225 @@
+use std::borrow::Cow;
+
+/// Maps characters a font lacks to the closest ones it may have.
+pub trait Transliterator {
+    /// Get the replacement for `ch`, if any.
+    fn transliterate(&self, ch: char) -> Option<Cow<'static, str>>;
+}
+
+impl<F> Transliterator for F
+where
+    F: Fn(char) -> Option<Cow<'static, str>>,
+{
+    fn transliterate(&self, ch: char) -> Option<Cow<'static, str>> {
+        self(ch)
+    }
+}
+
+/// Transliterates accented Latin letters, typographic punctuation and
+/// Cyrillic and Greek letters to plain ASCII.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct BasicTransliterator;
+
+const TABLE: &[(&str, &str)] = &[
+    // Latin
+    ("ÀÁÂÃÄÅĀĂĄǍ", "A"),
+    ("àáâãäåāăąǎª", "a"),
+    ("ÇĆĈĊČ", "C"),
+    ("çćĉċč", "c"),
+    ("ĎĐÐ", "D"),
+    ("ďđð", "d"),
+    ("ÈÉÊËĒĔĖĘĚ", "E"),
+    ("èéêëēĕėęě", "e"),
+    ("ĜĞĠĢ", "G"),
+    ("ĝğġģ", "g"),
+    ("ĤĦ", "H"),
+    ("ĥħ", "h"),
+    ("ÌÍÎÏĨĪĬĮİǏ", "I"),
+    ("ìíîïĩīĭįıǐ", "i"),
+    ("Ĵ", "J"),
+    ("ĵ", "j"),
+    ("Ķ", "K"),
+    ("ķĸ", "k"),
+    ("ĹĻĽĿŁ", "L"),
+    ("ĺļľŀł", "l"),
+    ("ÑŃŅŇŊ", "N"),
+    ("ñńņňŉŋ", "n"),
+    ("ÒÓÔÕÖØŌŎŐǑ", "O"),
+    ("òóôõöøōŏőǒº", "o"),
+    ("ŔŖŘ", "R"),
+    ("ŕŗř", "r"),
+    ("ŚŜŞŠȘ", "S"),
+    ("śŝşšșſ", "s"),
+    ("ŢŤŦȚ", "T"),
+    ("ţťŧț", "t"),
+    ("ÙÚÛÜŨŪŬŮŰŲǓ", "U"),
+    ("ùúûüũūŭůűųǔ", "u"),
+    ("Ŵ", "W"),
+    ("ŵ", "w"),
+    ("ÝŶŸ", "Y"),
+    ("ýÿŷ", "y"),
+    ("ŹŻŽ", "Z"),
+    ("źżž", "z"),
+    ("Æ", "AE"),
+    ("æ", "ae"),
+    ("Œ", "OE"),
+    ("œ", "oe"),
+    ("Þ", "TH"),
+    ("þ", "th"),
+    ("ß", "ss"),
+    ("Ĳ", "IJ"),
+    ("ĳ", "ij"),
+    // Punctuation and symbols
+    ("“”„‟″«»", "\""),
+    ("‘’‚‛′‹›", "'"),
+    ("‐‑‒–—―−", "-"),
+    ("…", "..."),
+    ("•·", "*"),
+    ("×", "x"),
+    ("÷", "/"),
+    ("\u{a0}\u{2002}\u{2003}\u{2009}\u{202f}\u{3000}", " "),
+    ("€", "EUR"),
+    ("©", "(C)"),
+    ("®", "(R)"),
+    ("™", "TM"),
+    // Cyrillic
+    ("А", "A"),
+    ("а", "a"),
+    ("Б", "B"),
+    ("б", "b"),
+    ("В", "V"),
+    ("в", "v"),
+    ("ГҐ", "G"),
+    ("гґ", "g"),
+    ("Д", "D"),
+    ("д", "d"),
+    ("ЕЭЄ", "E"),
+    ("еэє", "e"),
+    ("Ё", "Yo"),
+    ("ё", "yo"),
+    ("Ж", "Zh"),
+    ("ж", "zh"),
+    ("З", "Z"),
+    ("з", "z"),
+    ("ИІ", "I"),
+    ("иі", "i"),
+    ("Ї", "Yi"),
+    ("ї", "yi"),
+    ("ЙЫ", "Y"),
+    ("йы", "y"),
+    ("К", "K"),
+    ("к", "k"),
+    ("Л", "L"),
+    ("л", "l"),
+    ("М", "M"),
+    ("м", "m"),
+    ("Н", "N"),
+    ("н", "n"),
+    ("О", "O"),
+    ("о", "o"),
+    ("П", "P"),
+    ("п", "p"),
+    ("Р", "R"),
+    ("р", "r"),
+    ("С", "S"),
+    ("с", "s"),
+    ("Т", "T"),
+    ("т", "t"),
+    ("У", "U"),
+    ("у", "u"),
+    ("Ф", "F"),
+    ("ф", "f"),
+    ("Х", "Kh"),
+    ("х", "kh"),
+    ("Ц", "Ts"),
+    ("ц", "ts"),
+    ("Ч", "Ch"),
+    ("ч", "ch"),
+    ("Ш", "Sh"),
+    ("ш", "sh"),
+    ("Щ", "Shch"),
+    ("щ", "shch"),
+    ("Ъъ", "\""),
+    ("Ьь", "'"),
+    ("Ю", "Yu"),
+    ("ю", "yu"),
+    ("Я", "Ya"),
+    ("я", "ya"),
+    // Greek
+    ("ΑΆ", "A"),
+    ("αά", "a"),
+    ("Β", "B"),
+    ("β", "b"),
+    ("Γ", "G"),
+    ("γ", "g"),
+    ("Δ", "D"),
+    ("δ", "d"),
+    ("ΕΈ", "E"),
+    ("εέ", "e"),
+    ("Ζ", "Z"),
+    ("ζ", "z"),
+    ("ΗΉΙΊΪ", "I"),
+    ("ηήιίϊΐ", "i"),
+    ("Θ", "Th"),
+    ("θ", "th"),
+    ("Κ", "K"),
+    ("κ", "k"),
+    ("Λ", "L"),
+    ("λ", "l"),
+    ("Μ", "M"),
+    ("μ", "m"),
+    ("Ν", "N"),
+    ("ν", "n"),
+    ("Ξ", "X"),
+    ("ξ", "x"),
+    ("ΟΌΩΏ", "O"),
+    ("οόωώ", "o"),
+    ("Π", "P"),
+    ("π", "p"),
+    ("Ρ", "R"),
+    ("ρ", "r"),
+    ("Σ", "S"),
+    ("σς", "s"),
+    ("Τ", "T"),
+    ("τ", "t"),
+    ("ΥΎΫ", "Y"),
+    ("υύϋΰ", "y"),
+    ("Φ", "F"),
+    ("φ", "f"),
+    ("Χ", "Ch"),
+    ("χ", "ch"),
+    ("Ψ", "Ps"),
+    ("ψ", "ps"),
+];
+
+impl Transliterator for BasicTransliterator {
+    fn transliterate(&self, ch: char) -> Option<Cow<'static, str>> {
+        TABLE
+            .iter()
+            .find(|(from, _)| from.contains(ch))
+            .map(|(_, to)| Cow::Borrowed(*to))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BasicTransliterator, Transliterator};
+
+    #[test]
+    fn basic() {
+        let t = BasicTransliterator;
+        let translit = |s: &str| -> String {
+            s.chars()
+                .map(|ch| match t.transliterate(ch) {
+                    Some(res) => res.into_owned(),
+                    None => ch.to_string(),
+                })
+                .collect()
+        };
+
+        assert_eq!(translit("Café Straße"), "Cafe Strasse");
+        assert_eq!(translit("“quote” – end"), "\"quote\" - end");
+        assert_eq!(translit("Привет"), "Privet");
+        assert_eq!(translit("Αθήνα"), "Athina");
+    }
+}
@@ -1,18 +1,24 @@
 use std::str::Chars;
 
-// TODO: map al space chars
-const SPACE_CHARS: &'static [char] = &[' ', '\t', '\r', '\n'];
+use crate::split::CharClass;
 
 pub struct SplitWords<'a> {
     chars: Chars<'a>,
     buffer: Option<char>,
+    classify: fn(char) -> CharClass,
 }
 
 impl<'a> SplitWords<'a> {
+    #[cfg(test)]
     pub fn new<'b>(s: &'b str) -> SplitWords<'b> {
+        SplitWords::with_classifier(s, crate::split::classify)
+    }
+
+    pub fn with_classifier<'b>(s: &'b str, classify: fn(char) -> CharClass) -> SplitWords<'b> {
         SplitWords {
             chars: s.chars(),
             buffer: None,
+            classify,
         }
     }
 }
@@ -29,8 +35,8 @@ impl<'a> Iterator for SplitWords<'a> {
         let mut res = String::new();
         loop {
             match self.chars.next() {
-                Some(buffer) => {
-                    if SPACE_CHARS.contains(&buffer) {
+                Some(buffer) => match (self.classify)(buffer) {
+                    CharClass::Space => {
                         if res.is_empty() {
                             return Some(buffer.to_string());
                         } else {
@@ -38,10 +44,18 @@ impl<'a> Iterator for SplitWords<'a> {
 
                             return Some(res);
                         }
-                    } else {
+                    }
+                    CharClass::Break => {
+                        if !res.is_empty() {
+                            return Some(res);
+                        }
+                    }
+                    CharClass::BreakAfter => {
                         res.push(buffer);
+                        return Some(res);
                     }
-                }
+                    CharClass::Word => res.push(buffer),
+                },
                 None => {
                     if res.is_empty() {
                         return None;
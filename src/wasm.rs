@@ -0,0 +1,115 @@
+//! JavaScript bindings, for `wasm32-unknown-unknown`.
+//!
+//! Fonts are only read from memory, options and results are passed as JSON.
+
+use figfont::FIGfont;
+use wasm_bindgen::prelude::*;
+
+use crate::{info::FontInfo, render::RenderOptions};
+
+enum Inner {
+    Owned(FIGfont),
+    #[cfg(feature = "bundled-fonts")]
+    Bundled(&'static FIGfont),
+}
+
+/// A FIGfont loaded in memory.
+#[wasm_bindgen]
+pub struct Font {
+    inner: Inner,
+}
+
+fn options(json: Option<String>) -> Result<RenderOptions, JsError> {
+    match json {
+        Some(json) => Ok(serde_json::from_str(&json)?),
+        None => Ok(RenderOptions::default()),
+    }
+}
+
+#[wasm_bindgen]
+impl Font {
+    /// Parse a plain `flf2a` font.
+    #[wasm_bindgen(constructor)]
+    pub fn new(bytes: &[u8]) -> Result<Font, JsError> {
+        Ok(Font {
+            inner: Inner::Owned(FIGfont::read_from(bytes)?),
+        })
+    }
+
+    /// Get a bundled font by its name.
+    #[cfg(feature = "bundled-fonts")]
+    pub fn bundled(name: &str) -> Result<Font, JsError> {
+        crate::fonts::by_name(name)
+            .map(|font| Font {
+                inner: Inner::Bundled(font),
+            })
+            .ok_or_else(|| JsError::new(&format!("no bundled font named {:?}", name)))
+    }
+
+    fn font(&self) -> &FIGfont {
+        match self.inner {
+            Inner::Owned(ref font) => font,
+            #[cfg(feature = "bundled-fonts")]
+            Inner::Bundled(font) => font,
+        }
+    }
+
+    /// Render `text` to rows joined by newlines, with the `RenderOptions`
+    /// in the `options` JSON.
+    pub fn render(&self, text: &str, options: Option<String>) -> Result<String, JsError> {
+        let rendered = self::options(options)?
+            .render(self.font(), text)
+            .map_err(|err| JsError::new(&err))?;
+        Ok(rendered.rows.join("\n"))
+    }
+
+    /// Render `text` to the JSON of a `Rendered`, with the `RenderOptions`
+    /// in the `options` JSON.
+    #[wasm_bindgen(js_name = renderJson)]
+    pub fn render_json(&self, text: &str, options: Option<String>) -> Result<String, JsError> {
+        let rendered = self::options(options)?
+            .render(self.font(), text)
+            .map_err(|err| JsError::new(&err))?;
+        Ok(serde_json::to_string(&rendered)?)
+    }
+
+    /// Get the JSON of the font's `FontInfo`.
+    pub fn info(&self) -> Result<String, JsError> {
+        Ok(serde_json::to_string(&FontInfo::new(self.font()))?)
+    }
+}
+
+/// List the names of the bundled fonts.
+#[cfg(feature = "bundled-fonts")]
+#[wasm_bindgen(js_name = bundledFonts)]
+pub fn bundled_fonts() -> Vec<String> {
+    crate::fonts::NAMES
+        .iter()
+        .map(|name| name.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Font;
+
+    #[test]
+    fn render() {
+        let font = Font::new(include_bytes!("../fonts/plain/term.flf")).unwrap();
+
+        assert_eq!(font.render("Hi", None).unwrap(), "Hi");
+        assert_eq!(
+            font.render(
+                "Hi",
+                Some(r#"{"width": 4, "justify": "right"}"#.to_string())
+            )
+            .unwrap(),
+            "  Hi"
+        );
+        assert!(font
+            .render_json("Hi", None)
+            .unwrap()
+            .starts_with(r#"{"rows":["Hi"],"width":2,"height":1,"#));
+        assert!(font.info().unwrap().contains(r#""height":1"#));
+    }
+}
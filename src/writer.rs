@@ -0,0 +1,524 @@
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use encoding::{all::ISO_8859_1, DecoderTrap, EncoderTrap, Encoding};
+use figfont::{header::Layout, subcharacter::SubCharacter, FIGfont, PrintDirection};
+use thiserror::Error;
+
+use crate::{font::FIGfontExt, info, lint::parse_code};
+
+/// The codes every FIGfont defines, in file order: printable ASCII then the
+/// Deutsch characters.
+pub(crate) const REQUIRED_CODES: [i32; 102] = {
+    let mut codes = [0; 102];
+    let mut i = 0;
+    while i < 95 {
+        codes[i] = 32 + i as i32;
+        i += 1;
+    }
+    let deutsch = [196, 214, 220, 228, 246, 252, 223];
+    let mut j = 0;
+    while j < deutsch.len() {
+        codes[95 + j] = deutsch[j];
+        j += 1;
+    }
+    codes
+};
+
+/// Endmarks tried in order, the first one not used by a glyph is chosen.
+const ENDMARKS: &[char] = &['@', '#', '%', '&', '*', '!', '+', '^'];
+
+#[derive(Debug, Error)]
+pub enum WriteError {
+    #[error("character {code} has {found} rows, the font is {expected} rows tall")]
+    Height {
+        code: i32,
+        expected: usize,
+        found: usize,
+    },
+    #[error("character {code} uses the hard blank {hardblank:?} as a symbol")]
+    HardBlank { code: i32, hardblank: String },
+    #[error("the hard blank {0:?} can't be used")]
+    InvalidHardBlank(String),
+    #[error("character {0} uses every possible endmark")]
+    Endmark(i32),
+    #[error("character {0} has a line break in a row")]
+    LineBreak(i32),
+    #[error("the font doesn't load: {0}")]
+    Load(#[from] figfont::error::Error),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// A glyph and the comment of its code tag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Glyph {
+    pub lines: Vec<Vec<SubCharacter>>,
+    pub comment: Option<String>,
+}
+
+/// An editable copy of a FIGfont, written back as a `flf2a` file.
+///
+/// figfont doesn't list the code-tagged characters of a font, so
+/// `FontWriter::new` looks them up in the whole Unicode range and down to
+/// -0xFFFF, stopping early when the header tells how many there are.
+/// Characters tagged with codes outside of it are lost:
+/// `FontWriter::read_from` and `FontWriter::load_from` read the code tags
+/// from the file instead.
+///
+/// Required characters with a comment are written code-tagged too, as fonts
+/// tag the Deutsch characters again to name them.
+#[derive(Debug, Clone)]
+pub struct FontWriter {
+    hardblank: String,
+    height: usize,
+    baseline: usize,
+    layout: Layout,
+    print_direction: PrintDirection,
+    comment: String,
+    glyphs: BTreeMap<i32, Glyph>,
+}
+
+impl FontWriter {
+    pub fn new(font: &FIGfont) -> FontWriter {
+        FontWriter::with_codes(font, codetagged(font))
+    }
+
+    /// Read a plain font, with every code-tagged character.
+    pub fn read_from(bytes: &[u8]) -> Result<FontWriter, WriteError> {
+        let font = FIGfont::read_from(bytes)?;
+        let codes = codetags(&font, bytes);
+        Ok(FontWriter::with_codes(&font, codes))
+    }
+
+    /// Load the font at `path`, with every code-tagged character unless
+    /// it's zipped.
+    pub fn load_from<P: AsRef<Path>>(path: P) -> Result<FontWriter, WriteError> {
+        let path = path.as_ref();
+        let bytes = fs::read(path)?;
+        if bytes.starts_with(b"flf2a") {
+            FontWriter::read_from(&bytes)
+        } else {
+            Ok(FontWriter::new(&FIGfont::load_from(path)?))
+        }
+    }
+
+    fn with_codes(font: &FIGfont, codes: Vec<i32>) -> FontWriter {
+        let header = font.header();
+        let mut writer = FontWriter::empty(header.height());
+        writer.hardblank = ISO_8859_1
+            .decode(header.hard_blank_char(), DecoderTrap::Replace)
+            .unwrap_or_else(|_| "$".to_string());
+        writer.baseline = header.baseline();
+        writer.layout = header.layout();
+        writer.print_direction = header.print_direction();
+        writer.comment = header.comment().into_owned();
+
+        for code in REQUIRED_CODES.iter().copied().chain(codes) {
+            let character = font.get(code);
+            writer.glyphs.insert(
+                code,
+                Glyph {
+                    lines: font.glyph(code).into_owned(),
+                    comment: character.comment().map(|comment| comment.into_owned()),
+                },
+            );
+        }
+
+        writer
+    }
+
+    /// Create a writer without glyphs, the required ones are written empty
+    /// until set.
+    pub(crate) fn empty(height: usize) -> FontWriter {
+        FontWriter {
+            hardblank: "$".to_string(),
+            height,
+            baseline: height,
+            layout: Layout::HORIZONTAL_KERNING,
+            print_direction: PrintDirection::LeftToRight,
+            comment: String::new(),
+            glyphs: BTreeMap::new(),
+        }
+    }
+
+    pub fn hardblank(&self) -> &str {
+        &self.hardblank
+    }
+
+    /// Set the hard blank character, glyphs keep their hard blanks.
+    pub fn set_hardblank<S: Into<String>>(&mut self, hardblank: S) {
+        self.hardblank = hardblank.into();
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn baseline(&self) -> usize {
+        self.baseline
+    }
+
+    pub fn set_baseline(&mut self, baseline: usize) {
+        self.baseline = baseline;
+    }
+
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    pub fn set_layout(&mut self, layout: Layout) {
+        self.layout = layout;
+    }
+
+    pub fn print_direction(&self) -> PrintDirection {
+        self.print_direction
+    }
+
+    pub fn set_print_direction(&mut self, print_direction: PrintDirection) {
+        self.print_direction = print_direction;
+    }
+
+    pub fn comment(&self) -> &str {
+        &self.comment
+    }
+
+    pub fn set_comment<S: Into<String>>(&mut self, comment: S) {
+        self.comment = comment.into();
+    }
+
+    pub fn glyph(&self, code: i32) -> Option<&Glyph> {
+        self.glyphs.get(&code)
+    }
+
+    pub fn glyphs(&self) -> impl Iterator<Item = (i32, &Glyph)> {
+        self.glyphs.iter().map(|(code, glyph)| (*code, glyph))
+    }
+
+    /// Set the glyph of `code`, code tagged with `comment`. Required
+    /// characters are only code tagged too with a comment.
+    pub fn set_glyph(&mut self, code: i32, lines: Vec<Vec<SubCharacter>>, comment: Option<String>) {
+        self.glyphs.insert(code, Glyph { lines, comment });
+    }
+
+    /// Remove the glyph of `code`, required characters are written empty.
+    pub fn remove_glyph(&mut self, code: i32) -> Option<Glyph> {
+        self.glyphs.remove(&code)
+    }
+
+    fn row(&self, line: &[SubCharacter]) -> String {
+        line.iter()
+            .map(|sch| match sch {
+                SubCharacter::Blank => self.hardblank.as_str(),
+                SubCharacter::Symbol(sym) => sym.as_str(),
+            })
+            .collect()
+    }
+
+    fn max_length(&self) -> usize {
+        self.glyphs
+            .values()
+            .flat_map(|glyph| glyph.lines.iter())
+            .map(|line| encode(&self.row(line)).len())
+            .max()
+            .unwrap_or(0)
+            + 2
+    }
+
+    fn codetagged(&self) -> impl Iterator<Item = (i32, &Glyph)> {
+        self.glyphs()
+            .filter(|(code, glyph)| !REQUIRED_CODES.contains(code) || glyph.comment.is_some())
+    }
+
+    /// Write the font in the `flf2a` format.
+    pub fn write_to<W: Write>(&self, mut writer: W) -> Result<(), WriteError> {
+        let hardblank = ISO_8859_1
+            .encode(&self.hardblank, EncoderTrap::Strict)
+            .ok()
+            .filter(|raw| raw.len() == 1 && !b" \r\n".contains(&raw[0]))
+            .ok_or_else(|| WriteError::InvalidHardBlank(self.hardblank.clone()))?;
+
+        let comment: Vec<&str> = if self.comment.is_empty() {
+            // the loader wants at least a comment line
+            vec![""]
+        } else {
+            self.comment.split('\n').collect()
+        };
+
+        writer.write_all(b"flf2a")?;
+        writer.write_all(&hardblank)?;
+        writeln!(
+            writer,
+            " {} {} {} {} {} {} {} {}",
+            self.height,
+            self.baseline,
+            self.max_length(),
+            info::old_layout(self.layout),
+            comment.len(),
+            match self.print_direction {
+                PrintDirection::LeftToRight => 0,
+                PrintDirection::RightToLeft => 1,
+            },
+            self.layout.bits(),
+            self.codetagged().count(),
+        )?;
+        for line in comment {
+            writer.write_all(&encode(line))?;
+            writer.write_all(b"\n")?;
+        }
+
+        let empty = Glyph {
+            lines: vec![Vec::new(); self.height],
+            comment: None,
+        };
+        for code in REQUIRED_CODES.iter() {
+            self.write_glyph(&mut writer, *code, self.glyph(*code).unwrap_or(&empty))?;
+        }
+        for (code, glyph) in self.codetagged() {
+            let sign = if code < 0 { "-" } else { "" };
+            write!(writer, "{}0x{:04X}", sign, code.unsigned_abs())?;
+            if let Some(ref comment) = glyph.comment {
+                writer.write_all(b" ")?;
+                writer.write_all(&encode(comment))?;
+            }
+            writer.write_all(b"\n")?;
+            self.write_glyph(&mut writer, code, glyph)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_glyph<W: Write>(
+        &self,
+        writer: &mut W,
+        code: i32,
+        glyph: &Glyph,
+    ) -> Result<(), WriteError> {
+        if glyph.lines.len() != self.height {
+            return Err(WriteError::Height {
+                code,
+                expected: self.height,
+                found: glyph.lines.len(),
+            });
+        }
+
+        let line_break = glyph.lines.iter().flatten().any(|sch| match sch {
+            SubCharacter::Symbol(sym) => sym.contains(['\n', '\r']),
+            SubCharacter::Blank => false,
+        });
+        if line_break {
+            return Err(WriteError::LineBreak(code));
+        }
+
+        let collision = glyph.lines.iter().flatten().any(|sch| match sch {
+            SubCharacter::Symbol(sym) => sym.contains(self.hardblank.as_str()),
+            SubCharacter::Blank => false,
+        });
+        if collision {
+            return Err(WriteError::HardBlank {
+                code,
+                hardblank: self.hardblank.clone(),
+            });
+        }
+        let rows: Vec<String> = glyph.lines.iter().map(|line| self.row(line)).collect();
+
+        let endmark = ENDMARKS
+            .iter()
+            .find(|mark| {
+                self.hardblank != mark.to_string() && rows.iter().all(|row| !row.contains(**mark))
+            })
+            .ok_or(WriteError::Endmark(code))?;
+
+        for (i, row) in rows.iter().enumerate() {
+            writer.write_all(&encode(row))?;
+            if i + 1 == rows.len() {
+                writeln!(writer, "{}{}", endmark, endmark)?;
+            } else {
+                writeln!(writer, "{}", endmark)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, WriteError> {
+        let mut res = Vec::new();
+        self.write_to(&mut res)?;
+        Ok(res)
+    }
+
+    /// Write the font to the file at `path`.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), WriteError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Load the written font.
+    pub fn to_font(&self) -> Result<FIGfont, WriteError> {
+        let bytes = self.to_bytes()?;
+        Ok(FIGfont::read_from(&bytes[..])?)
+    }
+}
+
+/// Encode `text` in ISO-8859-1 when possible, otherwise in UTF-8, which
+/// `FIGfontExt::glyph` decodes.
+fn encode(text: &str) -> Vec<u8> {
+    ISO_8859_1
+        .encode(text, EncoderTrap::Strict)
+        .unwrap_or_else(|_| text.as_bytes().to_vec())
+}
+
+/// Read the code tags of `font` from the plain font `bytes` it was parsed
+/// from: after the header, the comment and the required characters, each
+/// tag line is followed by the rows of its character.
+fn codetags(font: &FIGfont, bytes: &[u8]) -> Vec<i32> {
+    let header = font.header();
+    let height = header.height();
+    let mut lines = bytes.split(|&b| b == b'\n');
+    if bytes.ends_with(b"\n") {
+        lines.next_back();
+    }
+
+    let skip = 1 + header.comment().split('\n').count() + REQUIRED_CODES.len() * height;
+    let mut lines = lines.skip(skip);
+    let mut res = Vec::new();
+    while let Some(tag) = lines.next() {
+        let tag = String::from_utf8_lossy(tag);
+        if let Some(code) = parse_code(tag.trim_end_matches('\r').split(' ').next().unwrap_or("")) {
+            res.push(code);
+        }
+        lines.by_ref().take(height).for_each(drop);
+    }
+
+    res
+}
+
+/// Find the code-tagged characters of `font` that aren't required, closest
+/// to 0 first.
+pub(crate) fn codetagged(font: &FIGfont) -> Vec<i32> {
+    // the required characters tagged again have the comment of their tag
+    let retagged = REQUIRED_CODES
+        .iter()
+        .filter(|&&code| font.get(code).comment().is_some())
+        .count();
+    let expected = font
+        .header()
+        .codetag_count()
+        .map(|count| (count as usize).saturating_sub(retagged));
+    let missing = font.get(126);
+    let mut res = Vec::new();
+
+    let codes = (0..=0x10ffff).flat_map(|code| {
+        std::iter::once(code).chain(Some(-code).filter(|code| (-0xffff..0).contains(code)))
+    });
+    for code in codes {
+        if expected.is_some_and(|expected| res.len() >= expected) {
+            break;
+        }
+        if !std::ptr::eq(font.get(code), missing) && !REQUIRED_CODES.contains(&code) {
+            res.push(code);
+        }
+    }
+
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FontWriter, WriteError};
+    use figfont::{header::Layout, subcharacter::SubCharacter, FIGfont};
+
+    #[test]
+    fn round_trip() {
+        for name in ["standard", "banner", "ivrit", "term"] {
+            let path = format!("fonts/plain/{}.flf", name);
+            let font = FIGfont::load_from(&path).unwrap();
+            for writer in [
+                FontWriter::new(&font),
+                FontWriter::load_from(&path).unwrap(),
+            ] {
+                let copy = FIGfont::read_from(&writer.to_bytes().unwrap()[..]).unwrap();
+
+                let (a, b) = (font.header(), copy.header());
+                assert_eq!(a.hard_blank_char(), b.hard_blank_char());
+                assert_eq!(a.height(), b.height());
+                assert_eq!(a.baseline(), b.baseline());
+                assert_eq!(a.layout(), b.layout());
+                assert_eq!(a.comment(), b.comment());
+                assert_eq!(a.codetag_count(), b.codetag_count(), "{:?}", path);
+                for (code, _) in writer.glyphs() {
+                    assert_eq!(font.get(code).lines(), copy.get(code).lines(), "{:?}", path);
+                    assert_eq!(font.get(code).comment(), copy.get(code).comment());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn round_trip_codetags() {
+        // beyond Unicode and negative codes, which FontWriter::new misses
+        let mut font =
+            include_str!("../fonts/plain/standard.flf").replacen(" 24463 229", " 24463 232", 1);
+        for (tag, row) in [
+            ("0x110000", "x"),
+            ("-2 MINUS TWO", "y"),
+            ("-0x7FFFFFFF", "z"),
+        ] {
+            font.push_str(tag);
+            font.push('\n');
+            for _ in 0..5 {
+                font.push_str(row);
+                font.push_str("@\n");
+            }
+            font.push_str(row);
+            font.push_str("@@\n");
+        }
+        let font_bytes = font.as_bytes();
+
+        let writer = FontWriter::read_from(font_bytes).unwrap();
+        let font = FIGfont::read_from(font_bytes).unwrap();
+        let copy = writer.to_font().unwrap();
+        assert_eq!(copy.header().codetag_count(), Some(232));
+        for code in [0x110000, -2, -0x7fffffff, 0xe9] {
+            assert_eq!(font.get(code).lines(), copy.get(code).lines(), "{}", code);
+            assert_eq!(font.get(code).comment(), copy.get(code).comment());
+        }
+        assert_eq!(copy.get(-2).comment().unwrap().as_str(), "MINUS TWO");
+        assert_eq!(copy.get(0x110000).lines()[0][0], SubCharacter::from('x'));
+    }
+
+    #[test]
+    fn edit() {
+        let font = FIGfont::standard().unwrap();
+        let mut writer = FontWriter::new(&font);
+        let count = writer.codetagged().count() as u32;
+        writer.set_hardblank("#");
+        writer.set_layout(Layout::HORIZONTAL_KERNING);
+        writer.set_glyph(
+            0x263a,
+            vec![vec![SubCharacter::from(':'), SubCharacter::from(')')]; 6],
+            Some("SMILE".to_string()),
+        );
+
+        let copy = writer.to_font().unwrap();
+        assert_eq!(copy.header().hard_blank_char(), b"#");
+        assert_eq!(copy.header().layout(), Layout::HORIZONTAL_KERNING);
+        assert_eq!(copy.header().codetag_count(), Some(count + 1));
+        assert_eq!(copy.get(0x263a).lines()[0].len(), 2);
+        assert_eq!(copy.get(0x263a).comment().unwrap().as_str(), "SMILE");
+        assert!(copy.get(' ' as i32).lines()[0].contains(&SubCharacter::Blank));
+
+        writer.set_glyph(
+            'a' as i32,
+            vec![vec![SubCharacter::Symbol("a\nb".to_string())]; 6],
+            None,
+        );
+        assert!(matches!(writer.to_font(), Err(WriteError::LineBreak(97))));
+    }
+}